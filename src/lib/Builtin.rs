@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::rc::Rc;
+use crate::Builtins::CdBuiltin::CdBuiltin;
+use crate::Builtins::DirsBuiltin::DirsBuiltin;
+use crate::Builtins::LetBuiltin::LetBuiltin;
+use crate::Builtins::NiceBuiltin::NiceBuiltin;
+use crate::Builtins::PopdBuiltin::PopdBuiltin;
+use crate::Builtins::PushdBuiltin::PushdBuiltin;
+use crate::Builtins::SetBuiltin::SetBuiltin;
+use crate::Builtins::UlimitBuiltin::UlimitBuiltin;
+use crate::Builtins::UmaskBuiltin::UmaskBuiltin;
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+
+/// A command that runs inside the shell process and may modify its state.
+pub trait Builtin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError>;
+}
+
+pub struct BuiltinRegistry {
+	builtins: HashMap<String, Rc<dyn Builtin>>
+}
+
+impl BuiltinRegistry {
+	pub fn new() -> BuiltinRegistry {
+		return BuiltinRegistry {
+			builtins: HashMap::new()
+		};
+	}
+
+	pub fn with_defaults() -> BuiltinRegistry {
+		let mut registry = BuiltinRegistry::new();
+		registry.register("set", Rc::new(SetBuiltin::new()));
+		registry.register("cd", Rc::new(CdBuiltin::new()));
+		registry.register("pushd", Rc::new(PushdBuiltin::new()));
+		registry.register("popd", Rc::new(PopdBuiltin::new()));
+		registry.register("dirs", Rc::new(DirsBuiltin::new()));
+		registry.register("let", Rc::new(LetBuiltin::new()));
+		registry.register("ulimit", Rc::new(UlimitBuiltin::new()));
+		registry.register("umask", Rc::new(UmaskBuiltin::new()));
+		registry.register("nice", Rc::new(NiceBuiltin::new()));
+		return registry;
+	}
+
+	pub fn register(&mut self, name: &str, builtin: Rc<dyn Builtin>) {
+		self.builtins.insert(name.to_string(), builtin);
+	}
+
+	pub fn get(&self, name: &str) -> Option<Rc<dyn Builtin>> {
+		return self.builtins.get(name).cloned();
+	}
+
+	pub fn names(&self) -> Vec<String> {
+		return self.builtins.keys().cloned().collect();
+	}
+}
@@ -0,0 +1,125 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::ShellState::{ShellState, Variable};
+
+pub struct CdBuiltin {
+
+}
+
+// Shell variables win over the environment the shell was started with.
+pub fn lookup_variable(state: &ShellState, name: &str) -> Option<String> {
+	return match state.get_variable(name) {
+		Some(Variable::Scalar(value)) => Some(value.clone()),
+		_ => env::var(name).ok()
+	};
+}
+
+pub fn home_directory(state: &ShellState) -> Option<String> {
+	return lookup_variable(state, "HOME").or_else(|| lookup_variable(state, "USERPROFILE"));
+}
+
+// Removes `.` and `..` components without following symbolic links, the way `cd` reports paths.
+fn normalize_path(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {},
+			Component::ParentDir => {
+				normalized.pop();
+			},
+			component => normalized.push(component)
+		}
+	}
+	return normalized;
+}
+
+/// Makes `directory`, relative to the session's current directory, the new current directory.
+pub fn change_directory(state: &mut ShellState, directory: &Path) -> Result<(), String> {
+	let target = normalize_path(&state.current_directory().join(directory));
+	return match fs::metadata(&target) {
+		Ok(metadata) if metadata.is_dir() => {
+			state.set_current_directory(target);
+			Ok(())
+		},
+		Ok(_) => Err(format!("{}: Not a directory", directory.display())),
+		Err(err) => Err(format!("{}: {}", directory.display(), err))
+	};
+}
+
+fn is_explicitly_relative(target: &str) -> bool {
+	let path = Path::new(target);
+	return path.is_absolute() || matches!(path.components().next(), Some(Component::CurDir | Component::ParentDir));
+}
+
+// Looks `target` up in the directories of `CDPATH`. An empty entry stands for the current directory.
+// Also returns whether a non-empty entry matched, in which case `cd` prints where it went.
+fn search_cdpath(state: &ShellState, target: &str) -> Option<(PathBuf, bool)> {
+	if is_explicitly_relative(target) {
+		return None;
+	}
+	let cdpath = lookup_variable(state, "CDPATH")?;
+	return env::split_paths(&cdpath)
+		.map(|entry| (state.current_directory().join(&entry).join(target), !entry.as_os_str().is_empty()))
+		.find(|(candidate, _)| candidate.is_dir());
+}
+
+impl CdBuiltin {
+	pub fn new() -> CdBuiltin {
+		return CdBuiltin {};
+	}
+}
+
+impl Builtin for CdBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut print_directory = false;
+		let target = match arguments.first().map(|argument| argument.as_str()) {
+			Some("-") => match state.previous_directory() {
+				Some(previous) => {
+					print_directory = true;
+					previous.to_path_buf()
+				},
+				None => {
+					eprintln!("cd: OLDPWD not set");
+					return Ok(1);
+				}
+			},
+			Some(target) => match search_cdpath(state, target) {
+				Some((found, from_cdpath)) => {
+					print_directory = from_cdpath;
+					found
+				},
+				None => PathBuf::from(target)
+			},
+			None => match home_directory(state) {
+				Some(home) => PathBuf::from(home),
+				None => {
+					eprintln!("cd: no home directory");
+					return Ok(1);
+				}
+			}
+		};
+
+		if let Err(err) = change_directory(state, &target) {
+			eprintln!("cd: {}", err);
+			return Ok(1);
+		}
+		if print_directory {
+			if let Err(err) = writeln!(output, "{}", state.current_directory().display()) {
+				return Err(CommandError::CouldNotExecute {
+					reason: err.to_string()
+				});
+			}
+		}
+		return Ok(0);
+	}
+}
@@ -0,0 +1,129 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use crate::Builtin::Builtin;
+use crate::Builtins::CdBuiltin::home_directory;
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+
+pub struct DirsBuiltin {
+
+}
+
+/// The current directory followed by the `pushd` stack, as `dirs` numbers them.
+pub fn full_stack(state: &ShellState) -> Vec<PathBuf> {
+	let mut stack = vec![state.current_directory().to_path_buf()];
+	stack.extend(state.directory_stack.iter().cloned());
+	return stack;
+}
+
+/// Parses a `+N` or `-N` stack position, counted from the left or the right of `dirs`.
+pub fn parse_stack_index(argument: &str, stack_size: usize) -> Option<Result<usize, String>> {
+	let (from_left, number) = match argument.chars().next() {
+		Some('+') => (true, &argument[1..]),
+		Some('-') => (false, &argument[1..]),
+		_ => return None
+	};
+	let number = number.parse::<usize>().ok()?;
+	if number >= stack_size {
+		return Some(Err(format!("{}: directory stack index out of range", argument)));
+	}
+	return Some(Ok(if from_left { number } else { stack_size - 1 - number }));
+}
+
+fn display_directory(directory: &Path, home: Option<&Path>) -> String {
+	if let Some(rest) = home.and_then(|home| directory.strip_prefix(home).ok()) {
+		if rest.as_os_str().is_empty() {
+			return "~".to_string();
+		}
+		return format!("~/{}", rest.display());
+	}
+	return directory.display().to_string();
+}
+
+/// Writes the stack on one line the way `pushd` and `popd` print it.
+pub fn print_stack(state: &ShellState, output: &mut dyn Write) -> Result<(), CommandError> {
+	let home = home_directory(state).map(PathBuf::from);
+	let line: Vec<String> = full_stack(state).iter()
+		.map(|directory| display_directory(directory, home.as_deref()))
+		.collect();
+	if let Err(err) = writeln!(output, "{}", line.join(" ")) {
+		return Err(CommandError::CouldNotExecute {
+			reason: err.to_string()
+		});
+	}
+	return Ok(());
+}
+
+impl DirsBuiltin {
+	pub fn new() -> DirsBuiltin {
+		return DirsBuiltin {};
+	}
+}
+
+impl Builtin for DirsBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut long = false;
+		let mut per_line = false;
+		let mut numbered = false;
+		let mut position: Option<usize> = None;
+
+		let stack = full_stack(state);
+		for argument in arguments {
+			if let Some(index) = parse_stack_index(argument, stack.len()) {
+				match index {
+					Ok(index) => position = Some(index),
+					Err(err) => {
+						eprintln!("dirs: {}", err);
+						return Ok(1);
+					}
+				}
+				continue;
+			}
+			match argument.as_str() {
+				"-c" => {
+					state.directory_stack.clear();
+					return Ok(0);
+				},
+				"-l" => long = true,
+				"-p" => per_line = true,
+				"-v" => {
+					per_line = true;
+					numbered = true;
+				},
+				_ => {
+					eprintln!("dirs: {}: invalid option", argument);
+					return Ok(2);
+				}
+			}
+		}
+
+		let home = if long { None } else { home_directory(state).map(PathBuf::from) };
+		let entries: Vec<(usize, String)> = stack.iter()
+			.map(|directory| display_directory(directory, home.as_deref()))
+			.enumerate()
+			.filter(|(index, _)| position.is_none_or(|position| position == *index))
+			.collect();
+
+		let text = if numbered {
+			entries.iter().map(|(index, directory)| format!("{:>2}  {}\n", index, directory)).collect()
+		} else if per_line || position.is_some() {
+			entries.iter().map(|(_, directory)| format!("{}\n", directory)).collect()
+		} else {
+			let line: Vec<&str> = entries.iter().map(|(_, directory)| directory.as_str()).collect();
+			format!("{}\n", line.join(" "))
+		};
+
+		if let Err(err) = output.write_all(text.as_bytes()) {
+			return Err(CommandError::CouldNotExecute {
+				reason: err.to_string()
+			});
+		}
+		return Ok(0);
+	}
+}
@@ -0,0 +1,127 @@
+use std::ffi::OsString;
+use std::io::Write;
+use std::rc::Rc;
+use crate::Builtin::Builtin;
+use crate::CachingPathResolver::sync_search_path;
+use crate::Command::CommandError;
+use crate::PathResolver::PathResolver;
+use crate::ShellState::ShellState;
+
+pub struct HashBuiltin {
+	path_resolver: Rc<dyn PathResolver>
+}
+
+impl HashBuiltin {
+	pub fn new(path_resolver: Rc<dyn PathResolver>) -> HashBuiltin {
+		return HashBuiltin {
+			path_resolver: path_resolver
+		};
+	}
+
+	fn list(&self, state: &ShellState, reusable: bool) -> String {
+		let mut text = String::new();
+		for (name, entry) in state.command_hash.entries() {
+			if reusable {
+				text.push_str(&format!("hash -p {} {}\n", entry.path.to_string_lossy(), name));
+			} else {
+				text.push_str(&format!("{:>4}\t{}\n", entry.hits, entry.path.to_string_lossy()));
+			}
+		}
+		if text.is_empty() {
+			eprintln!("hash: hash table empty");
+		} else if !reusable {
+			text.insert_str(0, "hits\tcommand\n");
+		}
+		return text;
+	}
+}
+
+impl Builtin for HashBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut reset = false;
+		let mut forget = false;
+		let mut print_paths = false;
+		let mut reusable = false;
+		let mut path: Option<&String> = None;
+		let mut names = Vec::<&String>::new();
+
+		let mut arguments = arguments.iter();
+		while let Some(argument) = arguments.next() {
+			match argument.as_str() {
+				"-r" => reset = true,
+				"-d" => forget = true,
+				"-t" => print_paths = true,
+				"-l" => reusable = true,
+				"-p" => {
+					let Some(value) = arguments.next() else {
+						eprintln!("hash: -p requires an argument");
+						return Ok(2);
+					};
+					path = Some(value);
+				},
+				option if option.starts_with('-') && option.len() > 1 => {
+					eprintln!("hash: {}: invalid option", option);
+					return Ok(2);
+				},
+				_ => names.push(argument)
+			}
+		}
+
+		let mut text = String::new();
+		let mut exit_code = 0;
+
+		if reset {
+			state.command_hash.clear();
+		}
+		if names.is_empty() {
+			if path.is_some() || forget || print_paths {
+				eprintln!("hash: command name expected");
+				return Ok(2);
+			}
+			if !reset {
+				text = self.list(state, reusable);
+			}
+		}
+
+		sync_search_path(self.path_resolver.as_ref(), state);
+		for name in names {
+			if forget {
+				if !state.command_hash.remove(name) {
+					eprintln!("hash: {}: not found", name);
+					exit_code = 1;
+				}
+			} else if print_paths {
+				match state.command_hash.get(name) {
+					Some(entry) => text.push_str(&format!("{}\n", entry.path.to_string_lossy())),
+					None => {
+						eprintln!("hash: {}: not found", name);
+						exit_code = 1;
+					}
+				}
+			} else if let Some(path) = path {
+				state.command_hash.insert(name, OsString::from(path), 0);
+			} else {
+				match self.path_resolver.resolve_command_global(name) {
+					Ok(resolved) => state.command_hash.insert(name, resolved, 0),
+					Err(_) => {
+						eprintln!("hash: {}: not found", name);
+						exit_code = 1;
+					}
+				}
+			}
+		}
+
+		if let Err(err) = output.write_all(text.as_bytes()) {
+			return Err(CommandError::CouldNotExecute {
+				reason: err.to_string()
+			});
+		}
+		return Ok(exit_code);
+	}
+}
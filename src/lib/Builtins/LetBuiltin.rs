@@ -0,0 +1,43 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+use crate::jsh::Arithmetic::evaluate_arithmetic;
+
+pub struct LetBuiltin {
+
+}
+
+impl LetBuiltin {
+	pub fn new() -> LetBuiltin {
+		return LetBuiltin {};
+	}
+}
+
+impl Builtin for LetBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		_output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		if arguments.is_empty() {
+			eprintln!("let: expression expected");
+			return Ok(1);
+		}
+
+		let mut last_value = 0;
+		for argument in arguments {
+			last_value = match evaluate_arithmetic(argument, state) {
+				Ok(value) => value,
+				Err(err) => {
+					eprintln!("let: {}", err);
+					return Ok(1);
+				}
+			};
+		}
+
+		return Ok(if last_value != 0 { 0 } else { 1 });
+	}
+}
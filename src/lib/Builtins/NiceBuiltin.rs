@@ -0,0 +1,108 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::ProcessAttributes::is_privileged;
+use crate::ShellState::ShellState;
+
+pub struct NiceBuiltin {
+
+}
+
+pub const DEFAULT_NICE_ADJUSTMENT: i32 = 10;
+
+fn is_adjustment_option(word: &str) -> bool {
+	return word.starts_with("-n")
+		|| word.starts_with("--adjustment=")
+		|| word.strip_prefix('-').is_some_and(|number| number.parse::<i32>().is_ok());
+}
+
+// Counts the leading option words of `nice`; the words after them form the command to run.
+pub fn nice_word_count(words: &[Option<String>]) -> usize {
+	let mut count = 0;
+	loop {
+		match words.get(count).cloned().flatten() {
+			Some(word) if word == "-n" || word == "--adjustment" => count += 2,
+			Some(word) if is_adjustment_option(&word) => count += 1,
+			_ => return count.min(words.len())
+		}
+	}
+}
+
+pub fn parse_adjustment(words: &[String]) -> Result<Option<i32>, String> {
+	let mut adjustment = None;
+	let mut words = words.iter();
+	while let Some(word) = words.next() {
+		let value = if word == "-n" || word == "--adjustment" {
+			words.next().ok_or_else(|| format!("{} requires an argument", word))?.as_str()
+		} else if let Some(value) = word.strip_prefix("--adjustment=") {
+			value
+		} else if let Some(value) = word.strip_prefix("-n") {
+			value
+		} else if let Some(value) = word.strip_prefix('-').filter(|value| value.parse::<i32>().is_ok()) {
+			value
+		} else {
+			return Err(format!("{}: invalid option", word));
+		};
+		adjustment = Some(value.parse::<i32>().map_err(|_| format!("{}: invalid adjustment", value))?);
+	}
+	return Ok(adjustment);
+}
+
+// Computes the niceness children should run with after applying `adjustment`.
+pub fn adjusted_niceness(state: &ShellState, adjustment: i32) -> Result<i32, String> {
+	let Some(current) = state.process_attributes.effective_niceness() else {
+		return Err("not supported on this platform".to_string());
+	};
+	if adjustment < 0 && !is_privileged() {
+		return Err("cannot lower the niceness without privileges".to_string());
+	}
+	return Ok(current.saturating_add(adjustment).clamp(-20, 19));
+}
+
+impl NiceBuiltin {
+	pub fn new() -> NiceBuiltin {
+		return NiceBuiltin {};
+	}
+}
+
+impl Builtin for NiceBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let adjustment = match parse_adjustment(arguments) {
+			Ok(adjustment) => adjustment,
+			Err(err) => {
+				eprintln!("nice: {}", err);
+				return Ok(2);
+			}
+		};
+
+		let Some(adjustment) = adjustment else {
+			let Some(niceness) = state.process_attributes.effective_niceness() else {
+				eprintln!("nice: not supported on this platform");
+				return Ok(1);
+			};
+			if let Err(err) = writeln!(output, "{}", niceness) {
+				return Err(CommandError::CouldNotExecute {
+					reason: err.to_string()
+				});
+			}
+			return Ok(0);
+		};
+
+		return match adjusted_niceness(state, adjustment) {
+			Ok(niceness) => {
+				state.process_attributes.niceness = Some(niceness);
+				Ok(0)
+			},
+			Err(err) => {
+				eprintln!("nice: {}", err);
+				Ok(1)
+			}
+		};
+	}
+}
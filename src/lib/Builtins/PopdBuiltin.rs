@@ -0,0 +1,64 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Builtins::CdBuiltin::change_directory;
+use crate::Builtins::DirsBuiltin::{full_stack, parse_stack_index, print_stack};
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+
+pub struct PopdBuiltin {
+
+}
+
+impl PopdBuiltin {
+	pub fn new() -> PopdBuiltin {
+		return PopdBuiltin {};
+	}
+}
+
+impl Builtin for PopdBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		if arguments.len() > 1 {
+			eprintln!("popd: too many arguments");
+			return Ok(2);
+		}
+
+		let mut stack = full_stack(state);
+		if stack.len() < 2 {
+			eprintln!("popd: directory stack empty");
+			return Ok(1);
+		}
+
+		let index = match arguments.first() {
+			None => 0,
+			Some(argument) => match parse_stack_index(argument, stack.len()) {
+				Some(Ok(index)) => index,
+				Some(Err(err)) => {
+					eprintln!("popd: {}", err);
+					return Ok(1);
+				},
+				None => {
+					eprintln!("popd: {}: invalid argument", argument);
+					return Ok(2);
+				}
+			}
+		};
+
+		stack.remove(index);
+		// Removing the current directory moves to the next entry on the stack.
+		if index == 0 {
+			if let Err(err) = change_directory(state, &stack[0]) {
+				eprintln!("popd: {}", err);
+				return Ok(1);
+			}
+		}
+		state.directory_stack = stack[1..].to_vec();
+		print_stack(state, output)?;
+		return Ok(0);
+	}
+}
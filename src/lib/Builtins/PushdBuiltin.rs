@@ -0,0 +1,67 @@
+use std::io::Write;
+use std::path::PathBuf;
+use crate::Builtin::Builtin;
+use crate::Builtins::CdBuiltin::change_directory;
+use crate::Builtins::DirsBuiltin::{full_stack, parse_stack_index, print_stack};
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+
+pub struct PushdBuiltin {
+
+}
+
+impl PushdBuiltin {
+	pub fn new() -> PushdBuiltin {
+		return PushdBuiltin {};
+	}
+}
+
+impl Builtin for PushdBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		if arguments.len() > 1 {
+			eprintln!("pushd: too many arguments");
+			return Ok(2);
+		}
+
+		let mut stack = full_stack(state);
+		let new_stack = match arguments.first() {
+			// Without arguments the top two entries trade places.
+			None => {
+				if stack.len() < 2 {
+					eprintln!("pushd: no other directory");
+					return Ok(1);
+				}
+				stack.swap(0, 1);
+				stack
+			},
+			Some(argument) => match parse_stack_index(argument, stack.len()) {
+				Some(Ok(index)) => {
+					stack.rotate_left(index);
+					stack
+				},
+				Some(Err(err)) => {
+					eprintln!("pushd: {}", err);
+					return Ok(1);
+				},
+				None => {
+					stack.insert(0, PathBuf::from(argument));
+					stack
+				}
+			}
+		};
+
+		if let Err(err) = change_directory(state, &new_stack[0]) {
+			eprintln!("pushd: {}", err);
+			return Ok(1);
+		}
+		state.directory_stack = new_stack[1..].to_vec();
+		print_stack(state, output)?;
+		return Ok(0);
+	}
+}
@@ -0,0 +1,108 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::Deadline::{format_duration, parse_duration};
+use crate::ShellState::ShellState;
+
+pub struct SetBuiltin {
+
+}
+
+impl SetBuiltin {
+	pub fn new() -> SetBuiltin {
+		return SetBuiltin {};
+	}
+
+	fn option_flag<'a>(&self, state: &'a mut ShellState, name: &str) -> Option<&'a mut bool> {
+		return match name {
+			"pipefail" => Some(&mut state.options.pipefail),
+			"xtrace" => Some(&mut state.options.xtrace),
+			"verbose" => Some(&mut state.options.verbose),
+			_ => None
+		};
+	}
+
+	fn print_options(&self, state: &mut ShellState, output: &mut dyn Write) -> Result<i32, CommandError> {
+		let mut lines = Vec::<String>::new();
+		for name in ["pipefail", "verbose", "xtrace"] {
+			let enabled = *self.option_flag(state, name).unwrap();
+			lines.push(format!("{:<15} {}\n", name, if enabled { "on" } else { "off" }));
+		}
+		let timeout = state.options.command_timeout.map_or("off".to_string(), format_duration);
+		lines.push(format!("{:<15} {}\n", "timeout", timeout));
+
+		for line in lines {
+			if let Err(err) = output.write_all(line.as_bytes()) {
+				return Err(CommandError::CouldNotExecute {
+					reason: err.to_string()
+				});
+			}
+		}
+		return Ok(0);
+	}
+}
+
+impl Builtin for SetBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		if arguments.is_empty() {
+			return self.print_options(state, output);
+		}
+
+		let mut i = 0;
+		while i < arguments.len() {
+			if state.options.apply_flag(&arguments[i]) {
+				i += 1;
+				continue;
+			}
+
+			let enable = match arguments[i].as_str() {
+				"-o" => true,
+				"+o" => false,
+				other => {
+					eprintln!("set: unknown argument {}", other);
+					return Ok(2);
+				}
+			};
+
+			let Some(name) = arguments.get(i + 1) else {
+				return self.print_options(state, output);
+			};
+
+			if name == "timeout" {
+				if !enable {
+					state.options.command_timeout = None;
+					i += 2;
+					continue;
+				}
+				let Some(duration) = arguments.get(i + 2) else {
+					eprintln!("set: timeout requires a duration");
+					return Ok(2);
+				};
+				let Some(duration) = parse_duration(duration) else {
+					eprintln!("set: invalid duration {}", duration);
+					return Ok(2);
+				};
+				state.options.command_timeout = Some(duration);
+				i += 3;
+				continue;
+			}
+
+			match self.option_flag(state, name) {
+				Some(flag) => *flag = enable,
+				None => {
+					eprintln!("set: unknown option {}", name);
+					return Ok(2);
+				}
+			}
+			i += 2;
+		}
+
+		return Ok(0);
+	}
+}
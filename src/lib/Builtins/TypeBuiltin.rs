@@ -0,0 +1,194 @@
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+use crate::Builtin::Builtin;
+use crate::CachingPathResolver::sync_search_path;
+use crate::Command::CommandError;
+use crate::PathResolver::{CommandMatch, PathResolver};
+use crate::ShellState::ShellState;
+use crate::jsh::{lowered_builtin, KEYWORDS};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CommandKind {
+	Keyword,
+	Builtin,
+	File
+}
+
+impl CommandKind {
+	fn name(&self) -> &'static str {
+		return match self {
+			CommandKind::Keyword => "keyword",
+			CommandKind::Builtin => "builtin",
+			CommandKind::File => "file"
+		};
+	}
+}
+
+/// Explains how command names resolve. Registered as both `type` and `which`; `which` prints
+/// executable paths only.
+pub struct TypeBuiltin {
+	name: String,
+	path_resolver: Rc<dyn PathResolver>,
+	builtin_names: Vec<String>
+}
+
+impl TypeBuiltin {
+	pub fn new(name: &str, path_resolver: Rc<dyn PathResolver>, builtin_names: Vec<String>) -> TypeBuiltin {
+		return TypeBuiltin {
+			name: name.to_string(),
+			path_resolver: path_resolver,
+			builtin_names: builtin_names
+		};
+	}
+
+	fn shell_kind(&self, command_name: &str) -> Option<CommandKind> {
+		if KEYWORDS.contains(&command_name) {
+			return Some(CommandKind::Keyword);
+		}
+		if lowered_builtin(command_name).is_some() || self.builtin_names.iter().any(|name| name == command_name) {
+			return Some(CommandKind::Builtin);
+		}
+		return None;
+	}
+
+	// Where `./command_name` would run from, if that differs from the search path result.
+	fn local_difference(&self, state: &ShellState, command_name: &str, first_match: Option<&CommandMatch>) -> Option<String> {
+		let local = self.path_resolver.resolve_command_local(state.current_directory(), command_name).ok()?;
+		if !Path::new(&local).is_file() {
+			return None;
+		}
+		if first_match.is_some_and(|first| Path::new(&first.path) == Path::new(&local)) {
+			return None;
+		}
+		return Some(local.to_string_lossy().to_string());
+	}
+
+	fn describe(
+		&self,
+		command_name: &str,
+		state: &ShellState,
+		all: bool,
+		paths_only: bool,
+		kind_only: bool,
+		text: &mut String
+	) -> bool {
+		if command_name.contains(['/', '\\']) {
+			let Ok(path) = self.path_resolver.resolve_command_local(state.current_directory(), command_name) else {
+				return false;
+			};
+			let path = path.to_string_lossy();
+			if kind_only {
+				text.push_str(&format!("{}\n", CommandKind::File.name()));
+			} else if paths_only {
+				text.push_str(&format!("{}\n", path));
+			} else {
+				text.push_str(&format!("{} is {}\n", command_name, path));
+			}
+			return true;
+		}
+
+		let shell_kind = self.shell_kind(command_name);
+		if let Some(kind) = shell_kind {
+			if kind_only {
+				text.push_str(&format!("{}\n", kind.name()));
+			} else if paths_only {
+				text.push_str(&format!("{}: shell {}\n", command_name, kind.name()));
+			} else {
+				text.push_str(&format!("{} is a shell {}\n", command_name, kind.name()));
+			}
+			if !all {
+				return true;
+			}
+		}
+
+		let matches = self.path_resolver.resolve_command_all(command_name);
+		let shown = if all { matches.len() } else { matches.len().min(1) };
+		for (index, command_match) in matches[..shown].iter().enumerate() {
+			let path = command_match.path.to_string_lossy();
+			if kind_only {
+				text.push_str(&format!("{}\n", CommandKind::File.name()));
+				continue;
+			}
+			if paths_only {
+				text.push_str(&format!("{}\n", path));
+				continue;
+			}
+
+			let hashed = index == 0 && state.command_hash.get(command_name)
+				.is_some_and(|entry| entry.path == command_match.path);
+			if hashed {
+				text.push_str(&format!("{} is hashed ({})", command_name, path));
+			} else {
+				text.push_str(&format!("{} is {}", command_name, path));
+			}
+			if !command_match.extension.is_empty() {
+				text.push_str(&format!(" (matched extension {})", command_match.extension.to_string_lossy()));
+			}
+			text.push('\n');
+		}
+
+		if !paths_only && !kind_only {
+			if let Some(local) = self.local_difference(state, command_name, matches.first()) {
+				text.push_str(&format!("./{} would run {} instead\n", command_name, local));
+			}
+		}
+
+		return shell_kind.is_some() || !matches.is_empty();
+	}
+}
+
+impl Builtin for TypeBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut all = false;
+		let mut paths_only = self.name == "which";
+		let mut kind_only = false;
+		let mut names = Vec::<&String>::new();
+
+		for argument in arguments {
+			let Some(flags) = argument.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+				names.push(argument);
+				continue;
+			};
+			for flag in flags.chars() {
+				match flag {
+					'a' => all = true,
+					'p' => paths_only = true,
+					't' => kind_only = true,
+					flag => {
+						eprintln!("{}: -{}: invalid option", self.name, flag);
+						return Ok(2);
+					}
+				}
+			}
+		}
+
+		if names.is_empty() {
+			eprintln!("{}: command name expected", self.name);
+			return Ok(2);
+		}
+
+		sync_search_path(self.path_resolver.as_ref(), state);
+		let mut text = String::new();
+		let mut exit_code = 0;
+		for name in names {
+			if !self.describe(name, state, all, paths_only, kind_only, &mut text) {
+				eprintln!("{}: {}: not found", self.name, name);
+				exit_code = 1;
+			}
+		}
+
+		if let Err(err) = output.write_all(text.as_bytes()) {
+			return Err(CommandError::CouldNotExecute {
+				reason: err.to_string()
+			});
+		}
+		return Ok(exit_code);
+	}
+}
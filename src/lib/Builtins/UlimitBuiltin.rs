@@ -0,0 +1,161 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::ProcessAttributes::{is_privileged, resource_info, ResourceLimit, RESOURCES};
+use crate::ShellState::ShellState;
+
+pub struct UlimitBuiltin {
+
+}
+
+fn format_limit(value: Option<u64>, unit: u64) -> String {
+	return match value {
+		Some(value) => (value / unit).to_string(),
+		None => "unlimited".to_string()
+	};
+}
+
+fn exceeds(value: Option<u64>, limit: Option<u64>) -> bool {
+	return match (value, limit) {
+		(_, None) => false,
+		(None, Some(_)) => true,
+		(Some(value), Some(limit)) => value > limit
+	};
+}
+
+impl UlimitBuiltin {
+	pub fn new() -> UlimitBuiltin {
+		return UlimitBuiltin {};
+	}
+
+	fn print_limits(
+		&self,
+		state: &ShellState,
+		resources: &[char],
+		hard: bool,
+		describe: bool,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut text = String::new();
+		for resource in resources {
+			let info = resource_info(*resource).unwrap();
+			let Some(limit) = state.process_attributes.effective_limit(*resource) else {
+				eprintln!("ulimit: {}: not supported on this platform", info.description);
+				return Ok(1);
+			};
+			let value = format_limit(if hard { limit.hard } else { limit.soft }, info.unit);
+
+			if !describe {
+				text.push_str(&format!("{}\n", value));
+			} else if info.unit_name.is_empty() {
+				text.push_str(&format!("{:<32}(-{}) {}\n", info.description, info.option, value));
+			} else {
+				let unit = format!("({}, -{})", info.unit_name, info.option);
+				text.push_str(&format!("{:<24}{:>14} {}\n", info.description, unit, value));
+			}
+		}
+
+		if let Err(err) = output.write_all(text.as_bytes()) {
+			return Err(CommandError::CouldNotExecute {
+				reason: err.to_string()
+			});
+		}
+		return Ok(0);
+	}
+
+	fn set_limit(&self, state: &mut ShellState, resource: char, value: &str, soft: bool, hard: bool) -> i32 {
+		let info = resource_info(resource).unwrap();
+		let value = if value == "unlimited" {
+			None
+		} else {
+			match value.parse::<u64>().ok().and_then(|value| value.checked_mul(info.unit)) {
+				Some(value) => Some(value),
+				None => {
+					eprintln!("ulimit: {}: invalid number", value);
+					return 1;
+				}
+			}
+		};
+
+		let Some(current) = state.process_attributes.effective_limit(resource) else {
+			eprintln!("ulimit: {}: not supported on this platform", info.description);
+			return 1;
+		};
+
+		let set_both = !soft && !hard;
+		let limit = ResourceLimit {
+			resource: resource,
+			soft: if soft || set_both { value } else { current.soft },
+			hard: if hard || set_both { value } else { current.hard }
+		};
+
+		if exceeds(limit.hard, current.hard) && !is_privileged() {
+			eprintln!("ulimit: {}: cannot raise the hard limit", info.description);
+			return 1;
+		}
+		if exceeds(limit.soft, limit.hard) {
+			eprintln!("ulimit: {}: soft limit exceeds the hard limit", info.description);
+			return 1;
+		}
+
+		state.process_attributes.set_limit(limit);
+		return 0;
+	}
+}
+
+impl Builtin for UlimitBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let mut all = false;
+		let mut soft = false;
+		let mut hard = false;
+		let mut resources = Vec::<char>::new();
+		let mut value: Option<&String> = None;
+
+		for argument in arguments {
+			let Some(flags) = argument.strip_prefix('-').filter(|flags| !flags.is_empty()) else {
+				if value.is_some() {
+					eprintln!("ulimit: too many arguments");
+					return Ok(2);
+				}
+				value = Some(argument);
+				continue;
+			};
+
+			for flag in flags.chars() {
+				match flag {
+					'a' => all = true,
+					'S' => soft = true,
+					'H' => hard = true,
+					flag if resource_info(flag).is_some() => resources.push(flag),
+					flag => {
+						eprintln!("ulimit: -{}: invalid option", flag);
+						return Ok(2);
+					}
+				}
+			}
+		}
+
+		if all {
+			let resources: Vec<char> = RESOURCES.iter().map(|resource| resource.option).collect();
+			return self.print_limits(state, &resources, hard && !soft, true, output);
+		}
+		if resources.is_empty() {
+			resources.push('f');
+		}
+
+		return match value {
+			Some(value) if resources.len() == 1 => Ok(self.set_limit(state, resources[0], value, soft, hard)),
+			Some(_) => {
+				eprintln!("ulimit: only one limit can be set at a time");
+				Ok(2)
+			},
+			None => self.print_limits(state, &resources, hard && !soft, resources.len() > 1, output)
+		};
+	}
+}
@@ -0,0 +1,117 @@
+use std::io::Write;
+use crate::Builtin::Builtin;
+use crate::Command::CommandError;
+use crate::ShellState::ShellState;
+
+pub struct UmaskBuiltin {
+
+}
+
+const WHO_BITS: [(char, u32); 3] = [('u', 0o700), ('g', 0o070), ('o', 0o007)];
+
+fn symbolic_mask(mask: u32) -> String {
+	let allowed = !mask & 0o777;
+	let classes: Vec<String> = WHO_BITS.iter()
+		.map(|(who, bits)| {
+			let permissions = allowed & bits;
+			let mut text = format!("{}=", who);
+			for (permission, bit) in [('r', 0o444), ('w', 0o222), ('x', 0o111)] {
+				if permissions & bit & bits != 0 {
+					text.push(permission);
+				}
+			}
+			text
+		})
+		.collect();
+	return classes.join(",");
+}
+
+// Applies a chmod style symbolic mode such as `u=rwx,g+r,o-w` to the permissions a mask allows.
+fn apply_symbolic(mask: u32, mode: &str) -> Option<u32> {
+	let mut allowed = !mask & 0o777;
+	for clause in mode.split(',') {
+		let operator_index = clause.find(['=', '+', '-'])?;
+		let (who, rest) = clause.split_at(operator_index);
+		let mut who_bits = 0;
+		for c in who.chars() {
+			who_bits |= match c {
+				'a' => 0o777,
+				c => WHO_BITS.iter().find(|(class, _)| *class == c)?.1
+			};
+		}
+		if who.is_empty() {
+			who_bits = 0o777;
+		}
+
+		let mut chars = rest.chars();
+		let operator = chars.next()?;
+		let mut permission_bits = 0;
+		for c in chars {
+			permission_bits |= match c {
+				'r' => 0o444,
+				'w' => 0o222,
+				'x' => 0o111,
+				_ => return None
+			};
+		}
+		permission_bits &= who_bits;
+
+		allowed = match operator {
+			'=' => (allowed & !who_bits) | permission_bits,
+			'+' => allowed | permission_bits,
+			_ => allowed & !permission_bits
+		};
+	}
+	return Some(!allowed & 0o777);
+}
+
+impl UmaskBuiltin {
+	pub fn new() -> UmaskBuiltin {
+		return UmaskBuiltin {};
+	}
+}
+
+impl Builtin for UmaskBuiltin {
+	fn run(
+		&self,
+		arguments: &[String],
+		state: &mut ShellState,
+		_input: &str,
+		output: &mut dyn Write
+	) -> Result<i32, CommandError> {
+		let symbolic = arguments.first().is_some_and(|argument| argument == "-S");
+		let arguments = if symbolic { &arguments[1..] } else { arguments };
+
+		let Some(mask) = state.process_attributes.effective_umask() else {
+			eprintln!("umask: not supported on this platform");
+			return Ok(1);
+		};
+
+		let Some(mode) = arguments.first() else {
+			let text = if symbolic { symbolic_mask(mask) } else { format!("{:04o}", mask) };
+			if let Err(err) = writeln!(output, "{}", text) {
+				return Err(CommandError::CouldNotExecute {
+					reason: err.to_string()
+				});
+			}
+			return Ok(0);
+		};
+
+		let new_mask = if mode.chars().all(|c| c.is_digit(8)) {
+			u32::from_str_radix(mode, 8).ok().filter(|mask| *mask <= 0o777)
+		} else {
+			apply_symbolic(mask, mode)
+		};
+
+		return match new_mask {
+			Some(new_mask) => {
+				state.process_attributes.umask = Some(new_mask);
+				Ok(0)
+			},
+			None => {
+				eprintln!("umask: {}: invalid mode", mode);
+				Ok(1)
+			}
+		};
+	}
+}
@@ -0,0 +1,11 @@
+pub mod SetBuiltin;
+pub mod CdBuiltin;
+pub mod LetBuiltin;
+pub mod UlimitBuiltin;
+pub mod UmaskBuiltin;
+pub mod NiceBuiltin;
+pub mod HashBuiltin;
+pub mod TypeBuiltin;
+pub mod DirsBuiltin;
+pub mod PushdBuiltin;
+pub mod PopdBuiltin;
\ No newline at end of file
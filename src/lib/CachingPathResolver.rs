@@ -0,0 +1,130 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Instant;
+use log::debug;
+use crate::Command::CommandError;
+use crate::PathResolver::{CommandMatch, PathResolver};
+use crate::ShellState::{ShellState, Variable};
+
+pub const SEARCH_PATH_VARIABLES: [&str; 2] = ["PATH", "Path"];
+
+#[derive(Debug, Clone)]
+pub struct CommandHashEntry {
+	pub path: OsString,
+	pub hits: u32
+}
+
+/// Remembered command lookups. Lives in the shell state so that changing the search path clears it.
+#[derive(Debug, Clone, Default)]
+pub struct CommandHash {
+	entries: BTreeMap<String, CommandHashEntry>,
+	applied_search_path: Option<String>
+}
+
+impl CommandHash {
+	pub fn new() -> CommandHash {
+		return CommandHash::default();
+	}
+
+	pub fn lookup(&mut self, command_name: &str) -> Option<OsString> {
+		let entry = self.entries.get_mut(command_name)?;
+		entry.hits += 1;
+		return Some(entry.path.clone());
+	}
+
+	pub fn get(&self, command_name: &str) -> Option<&CommandHashEntry> {
+		return self.entries.get(command_name);
+	}
+
+	pub fn insert(&mut self, command_name: &str, path: OsString, hits: u32) {
+		self.entries.insert(command_name.to_string(), CommandHashEntry {
+			path: path,
+			hits: hits
+		});
+	}
+
+	pub fn remove(&mut self, command_name: &str) -> bool {
+		return self.entries.remove(command_name).is_some();
+	}
+
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+
+	pub fn entries(&self) -> impl Iterator<Item = (&String, &CommandHashEntry)> {
+		return self.entries.iter();
+	}
+}
+
+fn search_path_value(state: &ShellState) -> Option<String> {
+	return SEARCH_PATH_VARIABLES.iter()
+		.find_map(|name| match state.get_variable(name) {
+			Some(Variable::Scalar(value)) => Some(value.clone()),
+			_ => None
+		});
+}
+
+/// Passes the shell's search path on to `path_resolver` if it changed since the last call.
+pub fn sync_search_path(path_resolver: &dyn PathResolver, state: &mut ShellState) {
+	let search_path = search_path_value(state);
+	if search_path == state.command_hash.applied_search_path {
+		return;
+	}
+	if let Some(search_path) = &search_path {
+		path_resolver.set_search_path(OsStr::new(search_path));
+	}
+	state.command_hash.applied_search_path = search_path;
+}
+
+/// Resolves commands through another resolver and remembers the results in the shell's command hash.
+pub struct CachingPathResolver {
+	inner: Rc<dyn PathResolver>,
+	state: Rc<RefCell<ShellState>>
+}
+
+impl CachingPathResolver {
+	pub fn new(inner: Rc<dyn PathResolver>, state: Rc<RefCell<ShellState>>) -> CachingPathResolver {
+		return CachingPathResolver {
+			inner: inner,
+			state: state
+		};
+	}
+}
+
+impl PathResolver for CachingPathResolver {
+	fn resolve_command_global(&self, command_name: &str) -> Result<OsString, CommandError> {
+		let started_at = Instant::now();
+		{
+			let mut state = self.state.borrow_mut();
+			sync_search_path(self.inner.as_ref(), &mut state);
+			if let Some(path) = state.command_hash.lookup(command_name) {
+				debug!("Resolved {} from the command hash in {:?}", command_name, started_at.elapsed());
+				return Ok(path);
+			}
+		}
+
+		let result = self.inner.resolve_command_global(command_name);
+		debug!("Looked up {} in {:?}", command_name, started_at.elapsed());
+
+		let path = result?;
+		self.state.borrow_mut().command_hash.insert(command_name, path.clone(), 1);
+		return Ok(path);
+	}
+
+	fn resolve_command_local(&self, current_directory: &Path, given_path: &str) -> Result<OsString, CommandError> {
+		return self.inner.resolve_command_local(current_directory, given_path);
+	}
+
+	fn resolve_command_all(&self, command_name: &str) -> Vec<CommandMatch> {
+		sync_search_path(self.inner.as_ref(), &mut self.state.borrow_mut());
+		return self.inner.resolve_command_all(command_name);
+	}
+
+	fn set_search_path(&self, search_path: &OsStr) {
+		self.state.borrow_mut().command_hash.clear();
+		self.inner.set_search_path(search_path);
+	}
+}
@@ -1,13 +1,75 @@
+use std::cell::RefCell;
+use crate::ShellState::ShellState;
+
 #[derive(Debug)]
 pub enum CommandError {
 	CouldNotExecute {
 		reason: String
+	},
+	/// The file exists but cannot be run.
+	NotExecutable {
+		reason: String
+	},
+	ExpansionFailed {
+		reason: String
 	}
 }
 
+impl CommandError {
+	pub fn reason(&self) -> &str {
+		return match self {
+			CommandError::CouldNotExecute { reason } => reason,
+			CommandError::NotExecutable { reason } => reason,
+			CommandError::ExpansionFailed { reason } => reason
+		};
+	}
+
+	pub fn exit_code(&self) -> i32 {
+		return match self {
+			CommandError::CouldNotExecute { .. } => COULD_NOT_EXECUTE_STATUS,
+			CommandError::NotExecutable { .. } => NOT_EXECUTABLE_STATUS,
+			CommandError::ExpansionFailed { .. } => EXPANSION_FAILED_STATUS
+		};
+	}
+}
+
+pub const COULD_NOT_EXECUTE_STATUS: i32 = 127;
+pub const NOT_EXECUTABLE_STATUS: i32 = 126;
+pub const EXPANSION_FAILED_STATUS: i32 = 1;
+
+/// A node of the executable command tree.
 pub trait Command {
 	fn execute(&self) -> Result<i32, CommandError>;
 	fn execute_redirected_output(&self) -> Result<(i32, String), CommandError>;
 	fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError>;
 	fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError>;
 }
+
+pub fn run_command(command: &dyn Command, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+	return match (input, capture_output) {
+		(None, false) => Ok((command.execute()?, String::new())),
+		(None, true) => command.execute_redirected_output(),
+		(Some(input), false) => Ok((command.execute_redirected_input(input)?, String::new())),
+		(Some(input), true) => command.execute_redirected_io(input)
+	};
+}
+
+/// Runs `command`, printing an error it fails with instead of returning it. Inside a sourced file
+/// the error is reported with the file and line of the running command.
+pub fn run_command_reporting_errors(
+	command: &dyn Command,
+	input: Option<&str>,
+	capture_output: bool,
+	state: &RefCell<ShellState>
+) -> (i32, String) {
+	return match run_command(command, input, capture_output) {
+		Ok(result) => result,
+		Err(err) => {
+			match state.borrow().source_location() {
+				Some(location) => eprintln!("{}: {}", location, err.reason()),
+				None => eprintln!("Error: {}", err.reason())
+			}
+			(err.exit_code(), String::new())
+		}
+	};
+}
@@ -1,7 +1,29 @@
-use std::io::Write;
+use std::cell::RefCell;
+use std::io::{BufRead, Write};
 
+/// A source of command lines. Returns `None` once there is no more input.
 pub trait CommandInterface {
-	fn read_command(&self, prompt: &str) -> String;
+	fn read_command(&self, prompt: &str) -> Option<String>;
+}
+
+impl<T: CommandInterface + ?Sized> CommandInterface for Box<T> {
+	fn read_command(&self, prompt: &str) -> Option<String> {
+		return (**self).read_command(prompt);
+	}
+}
+
+impl<T: CommandInterface + ?Sized> CommandInterface for &T {
+	fn read_command(&self, prompt: &str) -> Option<String> {
+		return (**self).read_command(prompt);
+	}
+}
+
+fn trim_line_ending(line: &mut String) {
+	if (line.ends_with("\r\n")) {
+		line.truncate(line.len() - 2);
+	} else if (line.ends_with("\n")) {
+		line.truncate(line.len() - 1);
+	}
 }
 
 pub struct Ecma48CommandInterface {
@@ -15,20 +37,43 @@ impl Ecma48CommandInterface {
 }
 
 impl CommandInterface for Ecma48CommandInterface {
-	fn read_command(&self, prompt: &str) -> String {
-		std::io::stdout().write("\n".as_bytes()).unwrap();
-		std::io::stdout().write(prompt.as_bytes()).unwrap();
+	fn read_command(&self, prompt: &str) -> Option<String> {
+		std::io::stdout().write_all("\n".as_bytes()).unwrap();
+		std::io::stdout().write_all(prompt.as_bytes()).unwrap();
 
 		std::io::stdout().flush().unwrap();
 
 		let mut line = String::new();
-		std::io::stdin().read_line(&mut line).unwrap();
+		if std::io::stdin().read_line(&mut line).unwrap() == 0 {
+			return None;
+		}
 
-		if (line.ends_with("\r\n")) {
-			line.truncate(line.len() - 2);
-		} else if (line.ends_with("\n")) {
-			line.truncate(line.len() - 1);
+		trim_line_ending(&mut line);
+		return Some(line);
+	}
+}
+
+pub struct ScriptCommandInterface<TReader: BufRead> {
+	reader: RefCell<TReader>
+}
+
+impl<TReader: BufRead> ScriptCommandInterface<TReader> {
+	pub fn new(reader: TReader) -> ScriptCommandInterface<TReader> {
+		return ScriptCommandInterface {
+			reader: RefCell::new(reader)
+		};
+	}
+}
+
+impl<TReader: BufRead> CommandInterface for ScriptCommandInterface<TReader> {
+	fn read_command(&self, _prompt: &str) -> Option<String> {
+		let mut line = String::new();
+		match self.reader.borrow_mut().read_line(&mut line) {
+			Ok(0) | Err(_) => return None,
+			Ok(_) => {}
 		}
-		return line;
+
+		trim_line_ending(&mut line);
+		return Some(line);
 	}
 }
\ No newline at end of file
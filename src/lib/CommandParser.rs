@@ -1,18 +1,32 @@
 use std::fmt::Debug;
-use super::Commands;
 use super::Command::Command;
-use super::Commands::ExecuteCommand::ExecuteCommand;
-extern crate pest;
-
-use pest::Parser;
 
+/// An error produced while parsing a command line.
 pub trait CommandParserError {
 	fn describe(&self) -> String;
+	fn is_incomplete(&self) -> bool;
 }
 
+/// Turns command line text into an executable [`Command`]. `Ok(None)` means there was nothing to run.
 pub trait CommandParser {
 	type TCommandParserError: CommandParserError + Debug + Clone;
 
-	fn parse_command(&self, command: &str) -> Result<Box<dyn Command>, Self::TCommandParserError>;
+	fn parse_command(&self, command: &str) -> Result<Option<Box<dyn Command>>, Self::TCommandParserError>;
+}
+
+impl<T: CommandParser + ?Sized> CommandParser for Box<T> {
+	type TCommandParserError = T::TCommandParserError;
+
+	fn parse_command(&self, command: &str) -> Result<Option<Box<dyn Command>>, Self::TCommandParserError> {
+		return (**self).parse_command(command);
+	}
+}
+
+impl<T: CommandParser + ?Sized> CommandParser for &T {
+	type TCommandParserError = T::TCommandParserError;
+
+	fn parse_command(&self, command: &str) -> Result<Option<Box<dyn Command>>, Self::TCommandParserError> {
+		return (**self).parse_command(command);
+	}
 }
 
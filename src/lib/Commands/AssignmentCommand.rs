@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError};
+use crate::ShellState::{ShellState, Variable};
+use crate::Trace::{trace_line, trace_quote};
+use crate::Word::Word;
+
+pub struct AssignmentCommand {
+    assignments: Vec<(String, Word)>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl AssignmentCommand {
+    pub fn new(assignments: Vec<(String, Word)>, state: Rc<RefCell<ShellState>>) -> AssignmentCommand {
+        return AssignmentCommand {
+            assignments: assignments,
+            state: state
+        };
+    }
+
+    fn assign(&self) -> Result<i32, CommandError> {
+        for (name, value) in &self.assignments {
+            let value = value.expand_to_string(&mut self.state.borrow_mut())?;
+            trace_line(&self.state.borrow(), &format!("{}={}", name, trace_quote(&value)));
+            self.state.borrow_mut().set_variable(name, Variable::Scalar(value));
+        }
+        return Ok(0);
+    }
+}
+
+impl Command for AssignmentCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return self.assign();
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return Ok((self.assign()?, String::new()));
+    }
+
+    fn execute_redirected_input(&self, _input: &str) -> Result<i32, CommandError> {
+        return self.assign();
+    }
+
+    fn execute_redirected_io(&self, _input: &str) -> Result<(i32, String), CommandError> {
+        return Ok((self.assign()?, String::new()));
+    }
+}
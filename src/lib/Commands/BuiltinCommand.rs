@@ -0,0 +1,72 @@
+use std::cell::RefCell;
+use std::io::{stdout, Write};
+use std::rc::Rc;
+use crate::Builtin::Builtin;
+use crate::Command::{Command, CommandError};
+use crate::ShellState::ShellState;
+use crate::Trace::trace_command;
+use crate::Word::Word;
+
+pub struct BuiltinCommand {
+    name: String,
+    builtin: Rc<dyn Builtin>,
+    arguments: Vec<Word>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl BuiltinCommand {
+    pub fn new(
+        name: String,
+        builtin: Rc<dyn Builtin>,
+        arguments: Vec<Word>,
+        state: Rc<RefCell<ShellState>>
+    ) -> BuiltinCommand {
+        return BuiltinCommand {
+            name: name,
+            builtin: builtin,
+            arguments: arguments,
+            state: state
+        };
+    }
+
+    fn run(&self, input: &str, output: &mut dyn Write) -> Result<i32, CommandError> {
+        let mut arguments = Vec::<String>::new();
+        for argument in &self.arguments {
+            arguments.extend(argument.expand(&mut self.state.borrow_mut())?);
+        }
+
+        let mut traced_words = vec![self.name.clone()];
+        traced_words.extend(arguments.iter().cloned());
+        trace_command(&self.state.borrow(), &traced_words);
+
+        return self.builtin.run(&arguments, &mut self.state.borrow_mut(), input, output);
+    }
+
+    fn run_captured(&self, input: &str) -> Result<(i32, String), CommandError> {
+        let mut output = Vec::<u8>::new();
+        let exit_code = self.run(input, &mut output)?;
+        return Ok((exit_code, String::from_utf8_lossy(&output).into_owned()));
+    }
+}
+
+impl Command for BuiltinCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        let exit_code = self.run("", &mut stdout())?;
+        stdout().flush().unwrap();
+        return Ok(exit_code);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_captured("");
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        let exit_code = self.run(input, &mut stdout())?;
+        stdout().flush().unwrap();
+        return Ok(exit_code);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_captured(input);
+    }
+}
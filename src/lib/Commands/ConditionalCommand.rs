@@ -0,0 +1,69 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError, run_command_reporting_errors};
+use crate::ShellState::ShellState;
+
+pub enum ConditionalOperator {
+    AND,
+    OR
+}
+
+pub struct ConditionalCommand {
+    left_command: Box<dyn Command>,
+    operator: ConditionalOperator,
+    right_command: Box<dyn Command>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl ConditionalCommand {
+    pub fn new(
+        left_command: Box<dyn Command>,
+        operator: ConditionalOperator,
+        right_command: Box<dyn Command>,
+        state: Rc<RefCell<ShellState>>
+    ) -> ConditionalCommand {
+        return ConditionalCommand {
+            left_command: left_command,
+            operator: operator,
+            right_command: right_command,
+            state: state
+        };
+    }
+
+    fn run(&self, input: Option<&str>, capture_output: bool) -> (i32, String) {
+        let (exit_code, mut output) = run_command_reporting_errors(self.left_command.as_ref(), input, capture_output, &self.state);
+        self.state.borrow_mut().set_last_status(exit_code);
+
+        let run_right = match self.operator {
+            ConditionalOperator::AND => exit_code == 0,
+            ConditionalOperator::OR => exit_code != 0
+        };
+        if !run_right {
+            return (exit_code, output);
+        }
+
+        let (exit_code, right_output) = run_command_reporting_errors(self.right_command.as_ref(), None, capture_output, &self.state);
+        self.state.borrow_mut().set_last_status(exit_code);
+        output.push_str(&right_output);
+
+        return (exit_code, output);
+    }
+}
+
+impl Command for ConditionalCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run(None, false).0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return Ok(self.run(None, true));
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run(Some(input), false).0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return Ok(self.run(Some(input), true));
+    }
+}
@@ -1,179 +1,107 @@
-use std::env::current_dir;
-use std::io::{stdout, Stdout, Write};
-use std::path::Path;
-use std::process;
-use std::process::Stdio;
+use std::cell::RefCell;
 use std::rc::Rc;
-use crate::lib::Command::{Command, CommandError};
-use crate::lib::PathResolver::PathResolver;
-
+use log::debug;
+use crate::Command::{Command, CommandError};
+use crate::PathResolver::PathResolver;
+use crate::ProcessSpawner::{ProcessRequest, ProcessSpawner};
+use crate::ShellState::ShellState;
+use crate::Trace::trace_command;
+use crate::Word::Word;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum CommandScope {
+    /// `./name`, relative to the current directory.
     LOCAL,
+    /// A name containing a path separator, e.g. `/usr/bin/env` or `../tools/build`.
+    DIRECT,
+    /// A bare name looked up as a builtin or on the search path.
     ANY
 }
 
 pub struct ExecuteCommand {
-    command_executable: String,
+    command_executable: Word,
     command_scope: CommandScope,
-    arguments: Vec<String>,
-    path_resolver: Rc<dyn PathResolver>
+    arguments: Vec<Word>,
+    path_resolver: Rc<dyn PathResolver>,
+    process_spawner: Rc<dyn ProcessSpawner>,
+    state: Rc<RefCell<ShellState>>
 }
 
 impl ExecuteCommand {
 	pub fn new(
-        command_name: String,
+        command_name: Word,
         command_scope: CommandScope,
-        arguments: Vec<String>,
-        path_resolver: Rc<dyn PathResolver>
+        arguments: Vec<Word>,
+        path_resolver: Rc<dyn PathResolver>,
+        process_spawner: Rc<dyn ProcessSpawner>,
+        state: Rc<RefCell<ShellState>>
     ) -> ExecuteCommand {
 		return ExecuteCommand {
             command_executable: command_name,
             command_scope: command_scope,
             arguments: arguments,
-            path_resolver: path_resolver
+            path_resolver: path_resolver,
+            process_spawner: process_spawner,
+            state: state
         };
 	}
 
-    fn create_command(&self) -> Result<process::Command, CommandError> {
+    fn create_command(&self) -> Result<ProcessRequest, CommandError> {
+        let mut state = self.state.borrow_mut();
+        let mut words = self.command_executable.expand(&mut state)?;
+        for argument in &self.arguments {
+            words.extend(argument.expand(&mut state)?);
+        }
+        drop(state);
+
+        if words.is_empty() {
+            return Err(CommandError::CouldNotExecute {
+                reason: "Command name expanded to nothing".to_string()
+            });
+        }
+        let command_name = words.remove(0);
+
         let proc_path = match self.command_scope {
-            CommandScope::LOCAL => {
-                self.path_resolver.resolve_command_local(
-                    Path::new(current_dir()
-                        .expect("Should be able to get current directory").as_path()
-                    ),
-                    &self.command_executable
-                )?
+            CommandScope::LOCAL | CommandScope::DIRECT => {
+                let current_directory = self.state.borrow().current_directory().to_path_buf();
+                self.path_resolver.resolve_command_local(&current_directory, &command_name)?
             },
             CommandScope::ANY => {
                 self.path_resolver.resolve_command_global(
-                    &self.command_executable
+                    &command_name
                 )?
             }
         };
 
-        let mut proc = process::Command::new(proc_path);
+        let mut traced_words = vec![proc_path.to_string_lossy().to_string()];
+        traced_words.extend(words.iter().cloned());
+        trace_command(&self.state.borrow(), &traced_words);
 
-        proc.args(self.arguments.clone());
+        let mut request = ProcessRequest::new(proc_path, words);
+        request.deadline = self.state.borrow().deadline().cloned();
+        request.usage_recorders = self.state.borrow().usage_recorders().to_vec();
+        request.attributes = self.state.borrow().process_attributes.clone();
+        request.current_directory = Some(self.state.borrow().current_directory().to_path_buf());
+        debug!("Prepared {:?}", request);
 
-        return Ok(proc);
+        return Ok(request);
     }
 }
 
 impl Command for ExecuteCommand {
     fn execute(&self) -> Result<i32, CommandError> {
-        let result = self.create_command()?
-            .stdin(Stdio::inherit())
-            .stdout(Stdio::inherit())
-            .spawn();
-
-        if let Err(err) = result {
-            return Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            });
-        }
-
-        return match result.unwrap().wait() {
-            Err(err) => Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            }),
-            Ok(exit_status) => Ok(exit_status.code().unwrap())
-        }
+        return Ok(self.process_spawner.run(&self.create_command()?, None, false)?.0);
     }
 
     fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
-        let result = self.create_command()?
-            .stdin(Stdio::inherit())
-            .output();
-
-        return match result {
-            Err(err) => Err(
-                CommandError::CouldNotExecute {
-                    reason: err.to_string()
-                }
-            ),
-            Ok(output) => Ok((
-                output.status.code().expect("Process code should be available since the process exited"),
-                String::from_utf8(output.stdout).expect("invalid utf8")
-            ))
-        }
+        return self.process_spawner.run(&self.create_command()?, None, true);
     }
 
     fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
-        let mut child_result = self.create_command()?
-            .stdin(Stdio::piped())
-            .stdout(Stdio::inherit())
-            .spawn();
-
-        if let Err(err) = child_result {
-            return Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            });
-        }
-
-        let mut child = child_result.unwrap();
-
-        let mut stdin = child.stdin
-            .take()
-            .expect("stdin should be available since stdin has been set to piped");
-
-        if let Err(err) = stdin.write(input.as_bytes()) {
-            return Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            });
-        }
-
-        return match child.wait() {
-            Err(err) => Err(
-                CommandError::CouldNotExecute {
-                    reason: err.to_string()
-                }
-            ),
-            Ok(exit_status) => Ok(exit_status.code()
-                .expect("exit code should be available since the process exited")
-            )
-        }
+        return Ok(self.process_spawner.run(&self.create_command()?, Some(input), false)?.0);
     }
 
     fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
-        let mut child_result = self.create_command()?
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .spawn();
-
-        if let Err(err) = child_result {
-            return Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            });
-        }
-
-        let mut child = child_result.unwrap();
-
-        let mut stdin = child.stdin
-            .take()
-            .expect("stdin should be available since stdin has been set to piped");
-
-        if let Err(err) = stdin.write(input.as_bytes()) {
-            return Err(CommandError::CouldNotExecute {
-                reason: err.to_string()
-            });
-        }
-
-        let input_clone = String::from(input);
-
-        std::thread::spawn(move || {
-            stdin.write_all(input_clone.as_bytes()).expect("failed to write to stdin");
-        });
-
-        return match child.wait_with_output() {
-            Err(err) => Err(
-                CommandError::CouldNotExecute {
-                    reason: err.to_string()
-                }
-            ),
-            Ok(output) => Ok((
-                output.status.code().expect("Process code should be available since the process exited"),
-                String::from_utf8(output.stdout).expect("invalid utf8")
-            ))
-        }
+        return self.process_spawner.run(&self.create_command()?, Some(input), true);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,31 @@
+use crate::Command::{Command, CommandError};
+
+pub struct GroupCommand {
+    command: Box<dyn Command>
+}
+
+impl GroupCommand {
+    pub fn new(command: Box<dyn Command>) -> GroupCommand {
+        return GroupCommand {
+            command: command
+        };
+    }
+}
+
+impl Command for GroupCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return self.command.execute();
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.command.execute_redirected_output();
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return self.command.execute_redirected_input(input);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.command.execute_redirected_io(input);
+    }
+}
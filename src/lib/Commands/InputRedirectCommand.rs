@@ -0,0 +1,72 @@
+use std::cell::RefCell;
+use std::fs::read_to_string;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError};
+use crate::ShellState::ShellState;
+use crate::Trace::trace_quote;
+use crate::Word::Word;
+
+pub enum InputSource {
+    Text(Word),
+    File(Word)
+}
+
+pub struct InputRedirectCommand {
+    command: Box<dyn Command>,
+    input: InputSource,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl InputRedirectCommand {
+    pub fn new(command: Box<dyn Command>, input: InputSource, state: Rc<RefCell<ShellState>>) -> InputRedirectCommand {
+        return InputRedirectCommand {
+            command: command,
+            input: input,
+            state: state
+        };
+    }
+
+    fn input_text(&self) -> Result<(String, String), CommandError> {
+        return match &self.input {
+            InputSource::Text(text) => {
+                let text = text.expand_to_string(&mut self.state.borrow_mut())?;
+                Ok((format!("<<< {}", trace_quote(text.strip_suffix('\n').unwrap_or(&text))), text))
+            },
+            InputSource::File(path) => {
+                let path = path.expand_to_string(&mut self.state.borrow_mut())?;
+                match read_to_string(self.state.borrow().resolve_path(&path)) {
+                    Ok(text) => Ok((format!("< {}", trace_quote(&path)), text)),
+                    Err(err) => Err(CommandError::CouldNotExecute {
+                        reason: format!("{}: {}", path, err)
+                    })
+                }
+            }
+        };
+    }
+
+    fn run_redirected<T>(&self, run: impl FnOnce(&str) -> Result<T, CommandError>) -> Result<T, CommandError> {
+        let (redirection, input) = self.input_text()?;
+        self.state.borrow_mut().push_trace_redirection(redirection);
+        let result = run(&input);
+        self.state.borrow_mut().pop_trace_redirection();
+        return result;
+    }
+}
+
+impl Command for InputRedirectCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return self.run_redirected(|input| self.command.execute_redirected_input(input));
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_redirected(|input| self.command.execute_redirected_io(input));
+    }
+
+    fn execute_redirected_input(&self, _input: &str) -> Result<i32, CommandError> {
+        return self.run_redirected(|input| self.command.execute_redirected_input(input));
+    }
+
+    fn execute_redirected_io(&self, _input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_redirected(|input| self.command.execute_redirected_io(input));
+    }
+}
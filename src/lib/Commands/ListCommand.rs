@@ -0,0 +1,56 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError, run_command_reporting_errors};
+use crate::ShellState::ShellState;
+
+pub struct ListCommand {
+    commands: Vec<Box<dyn Command>>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl ListCommand {
+    pub fn new(commands: Vec<Box<dyn Command>>, state: Rc<RefCell<ShellState>>) -> ListCommand {
+        return ListCommand {
+            commands: commands,
+            state: state
+        };
+    }
+
+    fn run(&self, input: Option<&str>, capture_output: bool) -> (i32, String) {
+        let mut exit_code = 0;
+        let mut output = String::new();
+
+        for (i, command) in self.commands.iter().enumerate() {
+            let command_input = if i == 0 { input } else { None };
+            let (command_exit_code, command_output) = run_command_reporting_errors(
+                command.as_ref(),
+                command_input,
+                capture_output,
+                &self.state
+            );
+            exit_code = command_exit_code;
+            output.push_str(&command_output);
+            self.state.borrow_mut().set_last_status(exit_code);
+        }
+
+        return (exit_code, output);
+    }
+}
+
+impl Command for ListCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run(None, false).0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return Ok(self.run(None, true));
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run(Some(input), false).0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return Ok(self.run(Some(input), true));
+    }
+}
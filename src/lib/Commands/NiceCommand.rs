@@ -0,0 +1,66 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Builtins::NiceBuiltin::{adjusted_niceness, parse_adjustment, DEFAULT_NICE_ADJUSTMENT};
+use crate::Command::{Command, CommandError, run_command};
+use crate::ShellState::ShellState;
+use crate::Trace::trace_command;
+use crate::Word::Word;
+
+pub struct NiceCommand {
+    options: Vec<Word>,
+    command: Box<dyn Command>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl NiceCommand {
+    pub fn new(options: Vec<Word>, command: Box<dyn Command>, state: Rc<RefCell<ShellState>>) -> NiceCommand {
+        return NiceCommand {
+            options: options,
+            command: command,
+            state: state
+        };
+    }
+
+    fn niceness(&self) -> Result<i32, CommandError> {
+        let mut words = Vec::<String>::new();
+        for option in &self.options {
+            words.extend(option.expand(&mut self.state.borrow_mut())?);
+        }
+
+        let mut traced_words = vec!["nice".to_string()];
+        traced_words.extend(words.iter().cloned());
+        trace_command(&self.state.borrow(), &traced_words);
+
+        let adjustment = parse_adjustment(&words)
+            .map_err(|reason| CommandError::CouldNotExecute { reason: format!("nice: {}", reason) })?
+            .unwrap_or(DEFAULT_NICE_ADJUSTMENT);
+        return adjusted_niceness(&self.state.borrow(), adjustment)
+            .map_err(|reason| CommandError::CouldNotExecute { reason: format!("nice: {}", reason) });
+    }
+
+    fn run_niced(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        let niceness = self.niceness()?;
+        let previous = self.state.borrow_mut().process_attributes.niceness.replace(niceness);
+        let result = run_command(self.command.as_ref(), input, capture_output);
+        self.state.borrow_mut().process_attributes.niceness = previous;
+        return result;
+    }
+}
+
+impl Command for NiceCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run_niced(None, false)?.0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_niced(None, true);
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run_niced(Some(input), false)?.0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_niced(Some(input), true);
+    }
+}
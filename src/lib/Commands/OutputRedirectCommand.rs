@@ -0,0 +1,83 @@
+use std::cell::RefCell;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError, run_command};
+use crate::ProcessAttributes::with_umask;
+use crate::ShellState::ShellState;
+use crate::Trace::trace_quote;
+use crate::Word::Word;
+
+pub struct OutputRedirectCommand {
+    command: Box<dyn Command>,
+    target: Word,
+    append: bool,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl OutputRedirectCommand {
+    pub fn new(
+        command: Box<dyn Command>,
+        target: Word,
+        append: bool,
+        state: Rc<RefCell<ShellState>>
+    ) -> OutputRedirectCommand {
+        return OutputRedirectCommand {
+            command: command,
+            target: target,
+            append: append,
+            state: state
+        };
+    }
+
+    fn run_redirected(&self, input: Option<&str>) -> Result<i32, CommandError> {
+        let target = self.target.expand_to_string(&mut self.state.borrow_mut())?;
+        let path = self.state.borrow().resolve_path(&target);
+        let umask = self.state.borrow().process_attributes.umask;
+        let file = with_umask(umask, || OpenOptions::new()
+            .write(true)
+            .create(true)
+            .append(self.append)
+            .truncate(!self.append)
+            .open(&path));
+
+        let mut file = match file {
+            Ok(file) => file,
+            Err(err) => return Err(CommandError::CouldNotExecute {
+                reason: format!("{}: {}", target, err)
+            })
+        };
+
+        let operator = if self.append { ">>" } else { ">" };
+        self.state.borrow_mut().push_trace_redirection(format!("{} {}", operator, trace_quote(&target)));
+        let result = run_command(self.command.as_ref(), input, true);
+        self.state.borrow_mut().pop_trace_redirection();
+        let (exit_code, output) = result?;
+
+        if let Err(err) = file.write_all(output.as_bytes()) {
+            return Err(CommandError::CouldNotExecute {
+                reason: format!("{}: {}", target, err)
+            });
+        }
+
+        return Ok(exit_code);
+    }
+}
+
+impl Command for OutputRedirectCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return self.run_redirected(None);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return Ok((self.run_redirected(None)?, String::new()));
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return self.run_redirected(Some(input));
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return Ok((self.run_redirected(Some(input))?, String::new()));
+    }
+}
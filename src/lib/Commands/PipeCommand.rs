@@ -1,51 +1,98 @@
-use crate::lib::Command::{Command, CommandError};
-use crate::lib::Command::CommandError::CouldNotExecute;
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError, run_command_reporting_errors};
+use crate::Deadline::{Deadline, DEFAULT_KILL_AFTER, SIGTERM, TIMED_OUT_STATUS};
+use crate::ShellState::{ShellState, Variable};
 
 pub struct PipeCommand {
-    left_command: Box<dyn Command>,
-    right_command: Box<dyn Command>
+    stages: Vec<Box<dyn Command>>,
+    state: Rc<RefCell<ShellState>>
 }
 
 impl PipeCommand {
-    pub fn new(left_command: Box<dyn Command>, right_command: Box<dyn Command>) -> PipeCommand {
+    pub fn new(stages: Vec<Box<dyn Command>>, state: Rc<RefCell<ShellState>>) -> PipeCommand {
         return PipeCommand {
-            left_command: left_command,
-            right_command: right_command,
+            stages: stages,
+            state: state
         };
     }
 
-    fn check_exit_code(&self, exit_code: i32, error_message: &str) -> Result<(), CommandError> {
-        if exit_code != 0 {
-            return Err(CommandError::CouldNotExecute {
-                reason: error_message.to_string()
-            });
+    fn default_deadline(&self) -> Option<Deadline> {
+        let state = self.state.borrow();
+        if state.deadline().is_some() {
+            return None;
         }
-        return Ok(());
+        return state.options.command_timeout
+            .map(|timeout| Deadline::new(timeout, SIGTERM, DEFAULT_KILL_AFTER));
+    }
+
+    fn run_stages(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        let deadline = self.default_deadline();
+        if let Some(deadline) = &deadline {
+            self.state.borrow_mut().push_deadline(deadline.clone());
+        }
+        let (exit_code, output) = self.run_stages_until_deadline(input, capture_output);
+        if deadline.is_some() {
+            self.state.borrow_mut().pop_deadline();
+        }
+
+        if deadline.is_some_and(|deadline| deadline.expired()) {
+            self.state.borrow_mut().set_last_status(TIMED_OUT_STATUS);
+            return Ok((TIMED_OUT_STATUS, output));
+        }
+        return Ok((exit_code, output));
+    }
+
+    fn run_stages_until_deadline(&self, input: Option<&str>, capture_output: bool) -> (i32, String) {
+        let mut statuses = Vec::<i32>::new();
+        let mut data: Option<String> = input.map(|input| input.to_string());
+
+        for (i, stage) in self.stages.iter().enumerate() {
+            let capture_stage_output = capture_output || i + 1 < self.stages.len();
+            // A stage that cannot be started counts as failed; the rest of the pipeline still runs.
+            let (exit_code, output) = run_command_reporting_errors(stage.as_ref(), data.as_deref(), capture_stage_output, &self.state);
+            statuses.push(exit_code);
+            data = Some(output);
+        }
+
+        let exit_code = self.pipeline_status(&statuses);
+
+        let mut state = self.state.borrow_mut();
+        state.set_variable(
+            "PIPESTATUS",
+            Variable::Array(statuses.iter().map(|status| status.to_string()).collect())
+        );
+        state.set_last_status(exit_code);
+
+        return (exit_code, data.unwrap_or_default());
+    }
+
+    fn pipeline_status(&self, statuses: &[i32]) -> i32 {
+        if self.state.borrow().options.pipefail {
+            return statuses.iter()
+                .rev()
+                .find(|status| **status != 0)
+                .copied()
+                .unwrap_or(0);
+        }
+        return statuses.last().copied().unwrap_or(0);
     }
 }
 
 impl Command for PipeCommand {
     fn execute(&self) -> Result<i32, CommandError> {
-        let out = self.right_command.execute_redirected_output()?;
-        self.check_exit_code(out.0, "Exit code from chained process was not 0")?;
-        return self.left_command.execute_redirected_input(&out.1);
+        return Ok(self.run_stages(None, false)?.0);
     }
 
     fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
-        let out = self.right_command.execute_redirected_output()?;
-        self.check_exit_code(out.0, "Exit code from chained process was not 0")?;
-        return self.left_command.execute_redirected_io(&out.1);
+        return self.run_stages(None, true);
     }
 
     fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
-        let out = self.right_command.execute_redirected_io(input)?;
-        self.check_exit_code(out.0, "Exit code from chained process was not 0")?;
-        return self.left_command.execute_redirected_input(&out.1);
+        return Ok(self.run_stages(Some(input), false)?.0);
     }
 
     fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
-        let out = self.right_command.execute_redirected_io(input)?;
-        self.check_exit_code(out.0, "Exit code from chained process was not 0")?;
-        return self.left_command.execute_redirected_io(&out.1);
+        return self.run_stages(Some(input), true);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,226 @@
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use log::debug;
+use crate::Builtin::BuiltinRegistry;
+use crate::Builtins::CdBuiltin::lookup_variable;
+use crate::Command::{Command, CommandError, run_command};
+use crate::CommandParser::CommandParserError;
+use crate::Jomsole::SYNTAX_ERROR_STATUS;
+use crate::PathResolver::PathResolver;
+use crate::ProcessSpawner::ProcessSpawner;
+use crate::ShellState::ShellState;
+use crate::Word::Word;
+use crate::jsh::Ast::Span;
+use crate::jsh::JshCommandParser::JshCommandParser;
+use crate::jsh::JshSyntaxParser::script_chunks;
+
+/// Directories `source` searches for file names without a path separator, before the current directory.
+pub const LIBRARY_PATH_VARIABLE: &str = "JSH_LIBRARY_PATH";
+
+/// `source FILE [ARGUMENTS...]` (or `. FILE`): runs a script in the current shell state.
+///
+/// `include FILE` is the form for library files: it takes no arguments, looks next to the including
+/// script first, and runs each file at most once per shell.
+pub struct SourceCommand {
+    arguments: Vec<Word>,
+    include: bool,
+    path_resolver: Rc<dyn PathResolver>,
+    process_spawner: Rc<dyn ProcessSpawner>,
+    state: Rc<RefCell<ShellState>>,
+    builtins: Rc<BuiltinRegistry>
+}
+
+// The directory of the script being run, or of the file being sourced from it.
+fn including_directory(state: &ShellState) -> Option<PathBuf> {
+    let script = match state.source_files().last() {
+        Some(file) => file.clone(),
+        None => Some(state.resolve_path(state.script_name())).filter(|path| path.is_file())?
+    };
+    return script.parent().map(Path::to_path_buf);
+}
+
+fn find_source_file(state: &ShellState, file_name: &str, include: bool) -> Option<PathBuf> {
+    let including_directory = if include { including_directory(state) } else { None };
+    if Path::new(file_name).is_absolute() {
+        return Some(state.resolve_path(file_name)).filter(|path| path.is_file());
+    }
+    if Path::new(file_name).components().count() > 1 {
+        return including_directory.map(|directory| directory.join(file_name))
+            .into_iter()
+            .chain(std::iter::once(state.resolve_path(file_name)))
+            .find(|path| path.is_file());
+    }
+
+    let library_path = lookup_variable(state, LIBRARY_PATH_VARIABLE).unwrap_or_default();
+    return including_directory.into_iter()
+        .chain(env::split_paths(&library_path).filter(|directory| !directory.as_os_str().is_empty()))
+        .map(|directory| state.current_directory().join(directory).join(file_name))
+        .chain(std::iter::once(state.resolve_path(file_name)))
+        .find(|path| path.is_file());
+}
+
+impl SourceCommand {
+    pub fn new(
+        arguments: Vec<Word>,
+        path_resolver: Rc<dyn PathResolver>,
+        process_spawner: Rc<dyn ProcessSpawner>,
+        state: Rc<RefCell<ShellState>>,
+        builtins: Rc<BuiltinRegistry>
+    ) -> SourceCommand {
+        return SourceCommand {
+            arguments: arguments,
+            include: false,
+            path_resolver: path_resolver,
+            process_spawner: process_spawner,
+            state: state,
+            builtins: builtins
+        };
+    }
+
+    /// Makes this an `include`.
+    pub fn include(mut self) -> SourceCommand {
+        self.include = true;
+        return self;
+    }
+
+    fn name(&self) -> &'static str {
+        return if self.include { "include" } else { "source" };
+    }
+
+    // Parses the next top-level command of `source` at a time, so that a syntax error only skips the
+    // command it is in, and runs it before reading on.
+    fn run_script(&self, path: &Path, source: &str, input: Option<&str>, capture_output: bool) -> (i32, String) {
+        let parser = JshCommandParser::new(
+            self.path_resolver.clone(),
+            self.process_spawner.clone(),
+            self.state.clone(),
+            self.builtins.clone()
+        );
+
+        let mut exit_code = 0;
+        let mut output = String::new();
+        let mut input = input;
+        for chunk in script_chunks(source) {
+            let program = match chunk.program {
+                Ok(program) => program,
+                Err(err) => {
+                    let position = (chunk.start + err.position().unwrap_or(0)).min(source.len());
+                    let (line, column) = Span::new(position, position).line_column(source);
+                    eprintln!("{}:{}:{}: {}", path.display(), line, column, err.describe());
+                    exit_code = SYNTAX_ERROR_STATUS;
+                    self.state.borrow_mut().set_last_status(exit_code);
+                    continue;
+                }
+            };
+
+            for (span, command) in parser.lower_script_lists(&program) {
+                let line = Span::new(chunk.start + span.start, chunk.start + span.start).line_column(source).0;
+                self.state.borrow_mut().set_source_line(line);
+                match run_command(command.as_ref(), input.take(), capture_output) {
+                    Ok((status, text)) => {
+                        exit_code = status;
+                        output.push_str(&text);
+                    },
+                    Err(err) => {
+                        eprintln!("{}:{}: {}", path.display(), line, err.reason());
+                        exit_code = err.exit_code();
+                        self.state.borrow_mut().set_last_status(exit_code);
+                    }
+                }
+            }
+        }
+        return (exit_code, output);
+    }
+
+    fn run_source(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        let mut words = Vec::<String>::new();
+        for argument in &self.arguments {
+            words.extend(argument.expand(&mut self.state.borrow_mut())?);
+        }
+        if words.is_empty() {
+            eprintln!("{}: filename argument required", self.name());
+            return Ok((2, String::new()));
+        }
+        if self.include && words.len() > 1 {
+            eprintln!("include: too many arguments");
+            return Ok((2, String::new()));
+        }
+        let file_name = words.remove(0);
+
+        let Some(path) = find_source_file(&self.state.borrow(), &file_name, self.include) else {
+            eprintln!("{}: {}: file not found", self.name(), file_name);
+            return Ok((1, String::new()));
+        };
+        return Ok(self.source_path(&path, words, input, capture_output));
+    }
+
+    /// Runs the script at `path` with `arguments` as its positional parameters. Errors inside the
+    /// script are reported with their line and do not stop it.
+    pub fn source_path(&self, path: &Path, arguments: Vec<String>, input: Option<&str>, capture_output: bool) -> (i32, String) {
+        let path = fs::canonicalize(path).unwrap_or(path.to_path_buf());
+
+        // A library that is already included, or still being included, is not run again.
+        if self.include && !self.state.borrow_mut().mark_included(path.clone()) {
+            debug!("Already included {:?}", path);
+            return (0, String::new());
+        }
+
+        let chain = self.state.borrow().source_files().to_vec();
+        if chain.contains(&path) {
+            let cycle: Vec<String> = chain.iter()
+                .skip_while(|file| **file != path)
+                .chain(std::iter::once(&path))
+                .map(|file| file.display().to_string())
+                .collect();
+            eprintln!("{}: recursive inclusion: {}", self.name(), cycle.join(" -> "));
+            return (1, String::new());
+        }
+
+        let source = match fs::read_to_string(&path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("{}: {}: {}", self.name(), path.display(), err);
+                return (1, String::new());
+            }
+        };
+        debug!("Sourcing {:?}", path);
+
+        // Arguments replace the positional parameters while the file runs; without any it sees the caller's.
+        let saved_parameters = if arguments.is_empty() {
+            None
+        } else {
+            Some(self.state.borrow_mut().set_positional_parameters(arguments))
+        };
+        self.state.borrow_mut().push_source_file(path.clone());
+
+        let (exit_code, output) = self.run_script(&path, &source, input, capture_output);
+
+        let mut state = self.state.borrow_mut();
+        state.pop_source_file();
+        if let Some(parameters) = saved_parameters {
+            state.set_positional_parameters(parameters);
+        }
+        return (exit_code, output);
+    }
+}
+
+impl Command for SourceCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run_source(None, false)?.0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_source(None, true);
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run_source(Some(input), false)?.0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_source(Some(input), true);
+    }
+}
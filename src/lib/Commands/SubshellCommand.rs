@@ -0,0 +1,43 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use crate::Command::{Command, CommandError, run_command};
+use crate::ShellState::ShellState;
+
+pub struct SubshellCommand {
+    command: Box<dyn Command>,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl SubshellCommand {
+    pub fn new(command: Box<dyn Command>, state: Rc<RefCell<ShellState>>) -> SubshellCommand {
+        return SubshellCommand {
+            command: command,
+            state: state
+        };
+    }
+
+    fn run_isolated(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        let saved_state = self.state.borrow().clone();
+        let result = run_command(self.command.as_ref(), input, capture_output);
+        *self.state.borrow_mut() = saved_state;
+        return result;
+    }
+}
+
+impl Command for SubshellCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run_isolated(None, false)?.0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_isolated(None, true);
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run_isolated(Some(input), false)?.0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_isolated(Some(input), true);
+    }
+}
@@ -0,0 +1,76 @@
+use std::cell::RefCell;
+use std::io::{stderr, Write};
+use std::rc::Rc;
+use std::time::Instant;
+use crate::Command::{Command, CommandError, run_command};
+use crate::ResourceUsage::{TimeReport, UsageRecorder};
+use crate::ShellState::{ShellState, Variable};
+
+/// The report `time -p` prints, regardless of `TIMEFORMAT`.
+const POSIX_TIME_FORMAT: &str = "real %R\nuser %U\nsys %S";
+
+pub struct TimeCommand {
+    command: Box<dyn Command>,
+    posix_format: bool,
+    state: Rc<RefCell<ShellState>>
+}
+
+impl TimeCommand {
+    pub fn new(command: Box<dyn Command>, posix_format: bool, state: Rc<RefCell<ShellState>>) -> TimeCommand {
+        return TimeCommand {
+            command: command,
+            posix_format: posix_format,
+            state: state
+        };
+    }
+
+    fn format_report(&self, report: &TimeReport) -> String {
+        if self.posix_format {
+            return report.format_template(POSIX_TIME_FORMAT);
+        }
+        return match self.state.borrow().get_variable("TIMEFORMAT") {
+            Some(Variable::Scalar(format)) if format == "json" => report.format_json(),
+            Some(Variable::Scalar(format)) => report.format_template(format),
+            _ => report.format_text()
+        };
+    }
+
+    fn run_timed(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        let recorder = UsageRecorder::new();
+        self.state.borrow_mut().push_usage_recorder(recorder.clone());
+        let started_at = Instant::now();
+        let result = run_command(self.command.as_ref(), input, capture_output);
+        let wall_time = started_at.elapsed();
+        self.state.borrow_mut().pop_usage_recorder();
+
+        let report = TimeReport {
+            wall_time: wall_time,
+            exit_code: match &result {
+                Ok((exit_code, _)) => *exit_code,
+                Err(err) => err.exit_code()
+            },
+            processes: recorder.processes()
+        };
+        let _ = stderr().write_all(self.format_report(&report).as_bytes());
+
+        return result;
+    }
+}
+
+impl Command for TimeCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run_timed(None, false)?.0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_timed(None, true);
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run_timed(Some(input), false)?.0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_timed(Some(input), true);
+    }
+}
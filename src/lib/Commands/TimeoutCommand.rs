@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+use log::debug;
+use crate::Command::{Command, CommandError, run_command};
+use crate::Deadline::{parse_duration, parse_signal, Deadline, DEFAULT_KILL_AFTER, SIGTERM, TIMED_OUT_STATUS, TIMEOUT_USAGE_STATUS};
+use crate::ShellState::ShellState;
+use crate::Trace::trace_command;
+use crate::Word::Word;
+
+pub struct TimeoutCommand {
+    options: Vec<Word>,
+    command: Option<Box<dyn Command>>,
+    state: Rc<RefCell<ShellState>>
+}
+
+fn takes_value(option: &str) -> bool {
+    return matches!(option, "-s" | "--signal" | "-k" | "--kill-after");
+}
+
+fn is_option(word: &str) -> bool {
+    return word.len() > 1 && word.starts_with('-') && !word[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.');
+}
+
+// Counts the leading words that belong to `timeout` itself: its options and the duration.
+// Words that are not known until expansion are treated as the duration.
+pub fn timeout_word_count(words: &[Option<String>]) -> usize {
+    let mut count = 0;
+    loop {
+        match words.get(count).cloned().flatten() {
+            Some(word) if takes_value(&word) => count += 2,
+            Some(word) if is_option(&word) => count += 1,
+            _ => return (count + 1).min(words.len())
+        }
+    }
+}
+
+fn parse_deadline(words: Vec<String>) -> Result<Deadline, String> {
+    let mut signal = SIGTERM;
+    let mut kill_after = DEFAULT_KILL_AFTER;
+    let mut timeout: Option<Duration> = None;
+    let mut words = words.into_iter();
+    while let Some(word) = words.next() {
+        let (option, value) = if takes_value(&word) {
+            let value = words.next().ok_or_else(|| format!("{} requires an argument", word))?;
+            (word, value)
+        } else if let Some(value) = word.strip_prefix("--signal=") {
+            ("-s".to_string(), value.to_string())
+        } else if let Some(value) = word.strip_prefix("--kill-after=") {
+            ("-k".to_string(), value.to_string())
+        } else if is_option(&word) && (word.starts_with("-s") || word.starts_with("-k")) {
+            (word[..2].to_string(), word[2..].to_string())
+        } else if is_option(&word) {
+            return Err(format!("unknown option {}", word));
+        } else {
+            timeout = Some(parse_duration(&word).ok_or_else(|| format!("invalid duration `{}`", word))?);
+            continue;
+        };
+
+        match option.as_str() {
+            "-s" | "--signal" => {
+                signal = parse_signal(&value).ok_or_else(|| format!("unknown signal `{}`", value))?;
+            },
+            _ => {
+                kill_after = parse_duration(&value).ok_or_else(|| format!("invalid duration `{}`", value))?;
+            }
+        }
+    }
+
+    let timeout = timeout.ok_or_else(|| "missing duration".to_string())?;
+    return Ok(Deadline::new(timeout, signal, kill_after));
+}
+
+impl TimeoutCommand {
+    pub fn new(
+        options: Vec<Word>,
+        command: Option<Box<dyn Command>>,
+        state: Rc<RefCell<ShellState>>
+    ) -> TimeoutCommand {
+        return TimeoutCommand {
+            options: options,
+            command: command,
+            state: state
+        };
+    }
+
+    fn expand_options(&self) -> Result<Vec<String>, CommandError> {
+        let mut words = Vec::<String>::new();
+        for option in &self.options {
+            words.extend(option.expand(&mut self.state.borrow_mut())?);
+        }
+
+        let mut traced_words = vec!["timeout".to_string()];
+        traced_words.extend(words.iter().cloned());
+        trace_command(&self.state.borrow(), &traced_words);
+        return Ok(words);
+    }
+
+    fn run_with_deadline(&self, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        // Bad options are reported with their own status so they are not mistaken for the command's.
+        let deadline = match parse_deadline(self.expand_options()?) {
+            Ok(deadline) => deadline,
+            Err(reason) => {
+                eprintln!("timeout: {}", reason);
+                return Ok((TIMEOUT_USAGE_STATUS, String::new()));
+            }
+        };
+        let Some(command) = &self.command else {
+            eprintln!("timeout: missing command");
+            return Ok((TIMEOUT_USAGE_STATUS, String::new()));
+        };
+
+        self.state.borrow_mut().push_deadline(deadline.clone());
+        let result = run_command(command.as_ref(), input, capture_output);
+        self.state.borrow_mut().pop_deadline();
+        let (exit_code, output) = result?;
+
+        if deadline.expired() {
+            debug!("Command timed out with status {}", exit_code);
+            return Ok((TIMED_OUT_STATUS, output));
+        }
+        return Ok((exit_code, output));
+    }
+}
+
+impl Command for TimeoutCommand {
+    fn execute(&self) -> Result<i32, CommandError> {
+        return Ok(self.run_with_deadline(None, false)?.0);
+    }
+
+    fn execute_redirected_output(&self) -> Result<(i32, String), CommandError> {
+        return self.run_with_deadline(None, true);
+    }
+
+    fn execute_redirected_input(&self, input: &str) -> Result<i32, CommandError> {
+        return Ok(self.run_with_deadline(Some(input), false)?.0);
+    }
+
+    fn execute_redirected_io(&self, input: &str) -> Result<(i32, String), CommandError> {
+        return self.run_with_deadline(Some(input), true);
+    }
+}
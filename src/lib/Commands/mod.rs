@@ -1,3 +1,14 @@
 pub mod ExecuteCommand;
 pub mod PipeCommand;
-pub mod BuiltinCommand;
\ No newline at end of file
+pub mod BuiltinCommand;
+pub mod InputRedirectCommand;
+pub mod OutputRedirectCommand;
+pub mod ListCommand;
+pub mod ConditionalCommand;
+pub mod SubshellCommand;
+pub mod GroupCommand;
+pub mod AssignmentCommand;
+pub mod TimeoutCommand;
+pub mod TimeCommand;
+pub mod NiceCommand;
+pub mod SourceCommand;
\ No newline at end of file
@@ -0,0 +1,112 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+pub const TIMED_OUT_STATUS: i32 = 124;
+/// The status of a `timeout` that was given bad options and ran nothing.
+pub const TIMEOUT_USAGE_STATUS: i32 = 125;
+pub const DEFAULT_KILL_AFTER: Duration = Duration::from_secs(5);
+
+#[cfg(unix)]
+const SIGNALS: &[(&str, i32)] = &[
+	("HUP", libc::SIGHUP),
+	("INT", libc::SIGINT),
+	("QUIT", libc::SIGQUIT),
+	("KILL", libc::SIGKILL),
+	("USR1", libc::SIGUSR1),
+	("USR2", libc::SIGUSR2),
+	("ALRM", libc::SIGALRM),
+	("TERM", libc::SIGTERM)
+];
+
+#[cfg(not(unix))]
+const SIGNALS: &[(&str, i32)] = &[
+	("HUP", 1),
+	("INT", 2),
+	("QUIT", 3),
+	("KILL", 9),
+	("ALRM", 14),
+	("TERM", 15)
+];
+
+pub const SIGTERM: i32 = 15;
+
+/// A point in time after which running processes are signalled and then killed.
+#[derive(Debug, Clone)]
+pub struct Deadline {
+	expires_at: Instant,
+	signal: i32,
+	kill_after: Duration,
+	expired: Rc<Cell<bool>>
+}
+
+impl Deadline {
+	pub fn new(timeout: Duration, signal: i32, kill_after: Duration) -> Deadline {
+		return Deadline {
+			expires_at: Instant::now() + timeout,
+			signal: signal,
+			kill_after: kill_after,
+			expired: Rc::new(Cell::new(false))
+		};
+	}
+
+	pub fn expires_at(&self) -> Instant {
+		return self.expires_at;
+	}
+
+	pub fn signal(&self) -> i32 {
+		return self.signal;
+	}
+
+	pub fn kill_after(&self) -> Duration {
+		return self.kill_after;
+	}
+
+	pub fn has_passed(&self) -> bool {
+		return Instant::now() >= self.expires_at;
+	}
+
+	pub fn expire(&self) {
+		self.expired.set(true);
+	}
+
+	pub fn expired(&self) -> bool {
+		return self.expired.get();
+	}
+}
+
+pub fn parse_duration(text: &str) -> Option<Duration> {
+	let (number, unit) = match text.find(|c: char| !c.is_ascii_digit() && c != '.') {
+		Some(index) => text.split_at(index),
+		None => (text, "")
+	};
+	let seconds = match unit {
+		"" | "s" => 1.0,
+		"ms" => 0.001,
+		"m" => 60.0,
+		"h" => 3600.0,
+		"d" => 86400.0,
+		_ => return None
+	};
+	let value = number.parse::<f64>().ok()?;
+	return Duration::try_from_secs_f64(value * seconds).ok();
+}
+
+pub fn format_duration(duration: Duration) -> String {
+	let millis = duration.as_millis();
+	if !millis.is_multiple_of(1000) {
+		return format!("{}ms", millis);
+	}
+	return format!("{}s", duration.as_secs());
+}
+
+pub fn parse_signal(text: &str) -> Option<i32> {
+	if let Ok(number) = text.parse::<i32>() {
+		return Some(number);
+	}
+	let name = text.to_ascii_uppercase();
+	let name = name.strip_prefix("SIG").unwrap_or(&name);
+	return SIGNALS.iter()
+		.find(|(signal_name, _)| *signal_name == name)
+		.map(|(_, number)| *number);
+}
@@ -1,6 +1,9 @@
+use std::cell::RefCell;
+use std::rc::Rc;
 use std::{thread, time};
-use crate::lib::Command::CommandError;
-use crate::lib::PathResolver::PathResolver;
+use crate::Command::CommandError;
+use crate::PathResolver::PathResolver;
+use crate::ShellState::ShellState;
 
 use super::{CommandParser::{CommandParser, CommandParserError}, CommandInterface::CommandInterface};
 
@@ -11,9 +14,13 @@ pub struct Jomsole<
 > {
 	command_parser: TCommandParser,
 	command_interface: TCommandInterface,
-	path_resolver: TPathResolver
+	path_resolver: TPathResolver,
+	interactive: bool,
+	state: Rc<RefCell<ShellState>>
 }
 
+pub const SYNTAX_ERROR_STATUS: i32 = 2;
+
 impl<
 	TCommandParser: CommandParser, 
 	TCommandInterface: CommandInterface,
@@ -22,46 +29,90 @@ impl<
 	pub fn new(
 		command_parser: TCommandParser,
 		command_interface: TCommandInterface,
-		path_resolver: TPathResolver
+		path_resolver: TPathResolver,
+		interactive: bool,
+		state: Rc<RefCell<ShellState>>
 	) -> Jomsole<TCommandParser, TCommandInterface, TPathResolver> {
 		return Jomsole {
 			command_parser: command_parser,
 			command_interface: command_interface,
-			path_resolver: path_resolver
+			path_resolver: path_resolver,
+			interactive: interactive,
+			state: state
 		};
 	}
 
-	pub fn run(&self) {
-		loop {
-			self.do_one_command();
-		}
+	pub fn run(&self) -> i32 {
+		while self.do_one_command() {}
+		return self.state.borrow().last_status();
+	}
+
+	fn set_last_status(&self, status: i32) {
+		self.state.borrow_mut().set_last_status(status);
 	}
 
-	fn do_one_command(&self) {
-		let command_text = self.command_interface.read_command("> ");
-		if command_text.len() == 0 {
-			return;
+	fn report_error(&self, message: String) {
+		if self.interactive {
+			println!("{}", message);
+		} else {
+			eprintln!("{}", message);
 		}
-		let command_result = self.command_parser.parse_command(&command_text);
-		let command = match command_result {
-			Err(err) => {
-				println!("Error occured: {}", err.describe());
-				return;
-			},
-			Ok(command) => {
-				command
+	}
+
+	fn do_one_command(&self) -> bool {
+		let Some(mut command_text) = self.command_interface.read_command("> ") else {
+			return false;
+		};
+
+		let command = loop {
+			let command_result = self.command_parser.parse_command(&command_text);
+			match command_result {
+				Err(err) if err.is_incomplete() => {
+					let Some(line) = self.command_interface.read_command(">> ") else {
+						self.report_error(format!("Error occured: {}", err.describe()));
+						self.set_last_status(SYNTAX_ERROR_STATUS);
+						return false;
+					};
+					command_text.push('\n');
+					command_text.push_str(&line);
+				},
+				Err(err) => {
+					self.report_error(format!("Error occured: {}", err.describe()));
+					self.set_last_status(SYNTAX_ERROR_STATUS);
+					return true;
+				},
+				Ok(None) => {
+					return true;
+				},
+				Ok(Some(command)) => {
+					break command;
+				}
 			}
 		};
 
 		let execution_result = command.execute();
-		if let Err(err) = execution_result {
-			match err {
-				CommandError::CouldNotExecute {reason} => {
-					println!("Error: {}", reason);
+		match execution_result {
+			Err(err) => {
+				self.set_last_status(err.exit_code());
+				match err {
+					CommandError::CouldNotExecute {reason} => {
+						self.report_error(format!("Error: {}", reason));
+					},
+					CommandError::NotExecutable {reason} => {
+						self.report_error(format!("Error: {}", reason));
+					},
+					CommandError::ExpansionFailed {reason} => {
+						self.report_error(format!("Error: {}", reason));
+					}
+				}
+			},
+			Ok(exit_code) => {
+				self.set_last_status(exit_code);
+				if self.interactive {
+					println!("Program exited with code {}", exit_code);
 				}
 			}
-		} else {
-			println!("Program exited with code {}", execution_result.unwrap());
 		}
+		return true;
 	}
 }
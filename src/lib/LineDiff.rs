@@ -0,0 +1,95 @@
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffLine {
+	Same(usize, usize),
+	Removed(usize),
+	Added(usize)
+}
+
+fn diff_lines(original: &[&str], modified: &[&str]) -> Vec<DiffLine> {
+	let mut common = vec![vec![0usize; modified.len() + 1]; original.len() + 1];
+	for i in (0..original.len()).rev() {
+		for j in (0..modified.len()).rev() {
+			common[i][j] = if original[i] == modified[j] {
+				common[i + 1][j + 1] + 1
+			} else {
+				common[i + 1][j].max(common[i][j + 1])
+			};
+		}
+	}
+
+	let mut lines = Vec::<DiffLine>::new();
+	let (mut i, mut j) = (0, 0);
+	while i < original.len() || j < modified.len() {
+		if i < original.len() && j < modified.len() && original[i] == modified[j] {
+			lines.push(DiffLine::Same(i, j));
+			i += 1;
+			j += 1;
+		} else if i < original.len() && (j == modified.len() || common[i + 1][j] >= common[i][j + 1]) {
+			lines.push(DiffLine::Removed(i));
+			i += 1;
+		} else {
+			lines.push(DiffLine::Added(j));
+			j += 1;
+		}
+	}
+	return lines;
+}
+
+pub fn unified_diff(original: &str, modified: &str, original_name: &str, modified_name: &str) -> String {
+	let original_lines: Vec<&str> = original.lines().collect();
+	let modified_lines: Vec<&str> = modified.lines().collect();
+	let lines = diff_lines(&original_lines, &modified_lines);
+
+	let changed: Vec<usize> = lines.iter()
+		.enumerate()
+		.filter(|(_, line)| !matches!(line, DiffLine::Same(_, _)))
+		.map(|(index, _)| index)
+		.collect();
+	if changed.is_empty() {
+		return String::new();
+	}
+
+	let mut output = format!("--- {}\n+++ {}\n", original_name, modified_name);
+	let mut hunk_start = 0;
+	while hunk_start < changed.len() {
+		let mut hunk_end = hunk_start;
+		while hunk_end + 1 < changed.len() && changed[hunk_end + 1] - changed[hunk_end] <= CONTEXT_LINES * 2 {
+			hunk_end += 1;
+		}
+
+		let first = changed[hunk_start].saturating_sub(CONTEXT_LINES);
+		let last = (changed[hunk_end] + CONTEXT_LINES).min(lines.len() - 1);
+		let hunk = &lines[first..=last];
+
+		let original_start = hunk.iter().find_map(|line| match line {
+			DiffLine::Same(i, _) | DiffLine::Removed(i) => Some(*i),
+			DiffLine::Added(_) => None
+		});
+		let modified_start = hunk.iter().find_map(|line| match line {
+			DiffLine::Same(_, j) | DiffLine::Added(j) => Some(*j),
+			DiffLine::Removed(_) => None
+		});
+		let original_count = hunk.iter().filter(|line| !matches!(line, DiffLine::Added(_))).count();
+		let modified_count = hunk.iter().filter(|line| !matches!(line, DiffLine::Removed(_))).count();
+
+		output.push_str(&format!(
+			"@@ -{},{} +{},{} @@\n",
+			original_start.map_or(0, |start| start + 1),
+			original_count,
+			modified_start.map_or(0, |start| start + 1),
+			modified_count
+		));
+		for line in hunk {
+			match line {
+				DiffLine::Same(i, _) => output.push_str(&format!(" {}\n", original_lines[*i])),
+				DiffLine::Removed(i) => output.push_str(&format!("-{}\n", original_lines[*i])),
+				DiffLine::Added(j) => output.push_str(&format!("+{}\n", modified_lines[*j]))
+			}
+		}
+
+		hunk_start = hunk_end + 1;
+	}
+	return output;
+}
@@ -1,8 +1,47 @@
-use std::ffi::OsString;
+use std::ffi::{OsStr, OsString};
 use std::path::Path;
-use crate::lib::Command::CommandError;
+use std::rc::Rc;
+use crate::Command::CommandError;
 
+/// One executable found for a command name, with the search extension (if any) that matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandMatch {
+    pub path: OsString,
+    pub extension: OsString
+}
+
+/// Resolves command names to executable paths, either through the search path or a directory.
 pub trait PathResolver {
     fn resolve_command_global(&self, command_name: &str) -> Result<OsString, CommandError>;
+    /// Resolves a path given directly, such as `./build` or `/usr/bin/env`, relative to `current_directory`.
     fn resolve_command_local(&self, current_directory: &Path, given_path: &str) -> Result<OsString, CommandError>;
+
+    /// Every executable `command_name` could resolve to, in search order. The first entry is the one
+    /// `resolve_command_global` picks.
+    fn resolve_command_all(&self, command_name: &str) -> Vec<CommandMatch> {
+        return self.resolve_command_global(command_name).into_iter()
+            .map(|path| CommandMatch { path: path, extension: OsString::new() })
+            .collect();
+    }
+
+    /// Called when the shell's search path variable changes. Resolvers without a search path ignore it.
+    fn set_search_path(&self, _search_path: &OsStr) {}
+}
+
+impl<T: PathResolver + ?Sized> PathResolver for Rc<T> {
+    fn resolve_command_global(&self, command_name: &str) -> Result<OsString, CommandError> {
+        return (**self).resolve_command_global(command_name);
+    }
+
+    fn resolve_command_local(&self, current_directory: &Path, given_path: &str) -> Result<OsString, CommandError> {
+        return (**self).resolve_command_local(current_directory, given_path);
+    }
+
+    fn resolve_command_all(&self, command_name: &str) -> Vec<CommandMatch> {
+        return (**self).resolve_command_all(command_name);
+    }
+
+    fn set_search_path(&self, search_path: &OsStr) {
+        (**self).set_search_path(search_path);
+    }
 }
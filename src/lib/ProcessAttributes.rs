@@ -0,0 +1,102 @@
+/// A resource limit in base units (bytes, seconds or a count). `None` means unlimited.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResourceLimit {
+	pub resource: char,
+	pub soft: Option<u64>,
+	pub hard: Option<u64>
+}
+
+pub struct ResourceInfo {
+	pub option: char,
+	pub description: &'static str,
+	pub unit_name: &'static str,
+	pub unit: u64
+}
+
+pub const RESOURCES: &[ResourceInfo] = &[
+	ResourceInfo { option: 'c', description: "core file size", unit_name: "blocks", unit: 512 },
+	ResourceInfo { option: 'd', description: "data seg size", unit_name: "kbytes", unit: 1024 },
+	ResourceInfo { option: 'f', description: "file size", unit_name: "blocks", unit: 512 },
+	ResourceInfo { option: 'l', description: "max locked memory", unit_name: "kbytes", unit: 1024 },
+	ResourceInfo { option: 'm', description: "max memory size", unit_name: "kbytes", unit: 1024 },
+	ResourceInfo { option: 'n', description: "open files", unit_name: "", unit: 1 },
+	ResourceInfo { option: 's', description: "stack size", unit_name: "kbytes", unit: 1024 },
+	ResourceInfo { option: 't', description: "cpu time", unit_name: "seconds", unit: 1 },
+	ResourceInfo { option: 'u', description: "max user processes", unit_name: "", unit: 1 },
+	ResourceInfo { option: 'v', description: "virtual memory", unit_name: "kbytes", unit: 1024 }
+];
+
+pub fn resource_info(option: char) -> Option<&'static ResourceInfo> {
+	return RESOURCES.iter().find(|resource| resource.option == option);
+}
+
+/// Attributes the shell applies to every child process before it runs.
+#[derive(Debug, Clone, Default)]
+pub struct ProcessAttributes {
+	limits: Vec<ResourceLimit>,
+	pub umask: Option<u32>,
+	pub niceness: Option<i32>
+}
+
+impl ProcessAttributes {
+	pub fn new() -> ProcessAttributes {
+		return ProcessAttributes::default();
+	}
+
+	pub fn limits(&self) -> &[ResourceLimit] {
+		return &self.limits;
+	}
+
+	pub fn set_limit(&mut self, limit: ResourceLimit) {
+		self.limits.retain(|existing| existing.resource != limit.resource);
+		self.limits.push(limit);
+	}
+
+	pub fn is_empty(&self) -> bool {
+		return self.limits.is_empty() && self.umask.is_none() && self.niceness.is_none();
+	}
+
+	/// The limit children will run with: the shell's override or the limit the shell inherited.
+	pub fn effective_limit(&self, resource: char) -> Option<ResourceLimit> {
+		if let Some(limit) = self.limits.iter().find(|limit| limit.resource == resource) {
+			return Some(*limit);
+		}
+		return current_limit(resource);
+	}
+
+	pub fn effective_umask(&self) -> Option<u32> {
+		return self.umask.or_else(current_umask);
+	}
+
+	pub fn effective_niceness(&self) -> Option<i32> {
+		return self.niceness.or_else(current_niceness);
+	}
+}
+
+#[cfg(unix)]
+pub use crate::unix::Resources::{current_limit, current_niceness, current_umask, is_privileged, with_umask};
+
+#[cfg(not(unix))]
+pub fn current_limit(_resource: char) -> Option<ResourceLimit> {
+	return None;
+}
+
+#[cfg(not(unix))]
+pub fn current_umask() -> Option<u32> {
+	return None;
+}
+
+#[cfg(not(unix))]
+pub fn with_umask<T>(_mask: Option<u32>, create: impl FnOnce() -> T) -> T {
+	return create();
+}
+
+#[cfg(not(unix))]
+pub fn current_niceness() -> Option<i32> {
+	return None;
+}
+
+#[cfg(not(unix))]
+pub fn is_privileged() -> bool {
+	return false;
+}
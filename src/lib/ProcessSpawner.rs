@@ -0,0 +1,249 @@
+use std::ffi::OsString;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process;
+use std::process::{Child, ExitStatus, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+use log::debug;
+use crate::Command::CommandError;
+use crate::Deadline::{Deadline, TIMED_OUT_STATUS};
+use crate::ProcessAttributes::ProcessAttributes;
+use crate::ResourceUsage::{ProcessUsage, ResourceUsage, UsageRecorder};
+
+/// Everything needed to start an external program.
+#[derive(Debug, Clone)]
+pub struct ProcessRequest {
+    pub program: OsString,
+    pub arguments: Vec<String>,
+    pub deadline: Option<Deadline>,
+    pub usage_recorders: Vec<UsageRecorder>,
+    pub attributes: ProcessAttributes,
+    /// The directory the child starts in. `None` inherits the shell process's directory.
+    pub current_directory: Option<PathBuf>
+}
+
+impl ProcessRequest {
+    pub fn new(program: OsString, arguments: Vec<String>) -> ProcessRequest {
+        return ProcessRequest {
+            program: program,
+            arguments: arguments,
+            deadline: None,
+            usage_recorders: Vec::new(),
+            attributes: ProcessAttributes::new(),
+            current_directory: None
+        };
+    }
+
+    pub fn record_usage(&self, exit_code: i32, wall_time: Duration, usage: ResourceUsage) {
+        for recorder in &self.usage_recorders {
+            recorder.record(ProcessUsage {
+                program: self.program.to_string_lossy().to_string(),
+                exit_code: exit_code,
+                wall_time: wall_time,
+                usage: usage.clone()
+            });
+        }
+    }
+}
+
+/// Starts external programs for `ExecuteCommand`.
+///
+/// `input` is written to the program's stdin when given, otherwise stdin is inherited. When
+/// `capture_output` is set the program's stdout is returned instead of being inherited.
+pub trait ProcessSpawner {
+    fn run(&self, request: &ProcessRequest, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError>;
+}
+
+const DEADLINE_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Runs programs as real operating system processes.
+pub struct SystemProcessSpawner {}
+
+fn could_not_execute(err: std::io::Error) -> CommandError {
+    return CommandError::CouldNotExecute {
+        reason: err.to_string()
+    };
+}
+
+#[cfg(unix)]
+fn exit_status_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+    return status.code()
+        .or(status.signal().map(|signal| 128 + signal))
+        .expect("Process should have exited or been signalled");
+}
+
+#[cfg(not(unix))]
+fn exit_status_code(status: ExitStatus) -> i32 {
+    return status.code().expect("Process code should be available since the process exited");
+}
+
+#[cfg(unix)]
+fn duration_from_timeval(time: libc::timeval) -> Duration {
+    return Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64);
+}
+
+// Waits with wait4 instead of Child::wait so the child's rusage is available.
+#[cfg(unix)]
+fn wait_for_exit(child: &mut Child, block: bool) -> Result<Option<(ExitStatus, ResourceUsage)>, CommandError> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status: libc::c_int = 0;
+    let mut rusage: libc::rusage = unsafe { std::mem::zeroed() };
+    let options = if block { 0 } else { libc::WNOHANG };
+    loop {
+        let pid = unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, options, &mut rusage) };
+        if pid == -1 {
+            let err = std::io::Error::last_os_error();
+            if err.kind() == std::io::ErrorKind::Interrupted {
+                continue;
+            }
+            return Err(could_not_execute(err));
+        }
+        if pid == 0 {
+            return Ok(None);
+        }
+        break;
+    }
+
+    let max_rss = rusage.ru_maxrss as u64;
+    let usage = ResourceUsage {
+        user_time: duration_from_timeval(rusage.ru_utime),
+        system_time: duration_from_timeval(rusage.ru_stime),
+        max_rss_kib: if cfg!(target_os = "macos") { max_rss / 1024 } else { max_rss },
+        voluntary_context_switches: rusage.ru_nvcsw as u64,
+        involuntary_context_switches: rusage.ru_nivcsw as u64
+    };
+    return Ok(Some((ExitStatus::from_raw(status), usage)));
+}
+
+#[cfg(not(unix))]
+fn wait_for_exit(child: &mut Child, block: bool) -> Result<Option<(ExitStatus, ResourceUsage)>, CommandError> {
+    let status = if block {
+        Some(child.wait().map_err(could_not_execute)?)
+    } else {
+        child.try_wait().map_err(could_not_execute)?
+    };
+    return Ok(status.map(|status| (status, ResourceUsage::new())));
+}
+
+#[cfg(unix)]
+fn send_signal(child: &mut Child, signal: i32) {
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, signal);
+    }
+}
+
+#[cfg(not(unix))]
+fn send_signal(child: &mut Child, _signal: i32) {
+    let _ = child.kill();
+}
+
+impl SystemProcessSpawner {
+    pub fn new() -> SystemProcessSpawner {
+        return SystemProcessSpawner {};
+    }
+
+    fn create_command(&self, request: &ProcessRequest) -> process::Command {
+        let mut proc = process::Command::new(&request.program);
+        proc.args(&request.arguments);
+        if let Some(directory) = &request.current_directory {
+            proc.current_dir(directory);
+        }
+        self.apply_attributes(&mut proc, &request.attributes);
+        return proc;
+    }
+
+    #[cfg(unix)]
+    fn apply_attributes(&self, proc: &mut process::Command, attributes: &ProcessAttributes) {
+        use std::os::unix::process::CommandExt;
+
+        if attributes.is_empty() {
+            return;
+        }
+        let attributes = attributes.clone();
+        unsafe {
+            proc.pre_exec(move || crate::unix::Resources::apply_process_attributes(&attributes));
+        }
+    }
+
+    #[cfg(not(unix))]
+    fn apply_attributes(&self, _proc: &mut process::Command, _attributes: &ProcessAttributes) {
+    }
+
+    fn wait_until(&self, child: &mut Child, deadline: &Deadline) -> Result<(ExitStatus, ResourceUsage), CommandError> {
+        let mut signalled_at: Option<Instant> = None;
+        loop {
+            if let Some(exited) = wait_for_exit(child, false)? {
+                return Ok(exited);
+            }
+
+            match signalled_at {
+                None if deadline.has_passed() => {
+                    debug!("Deadline passed, sending signal {} to process {}", deadline.signal(), child.id());
+                    deadline.expire();
+                    send_signal(child, deadline.signal());
+                    signalled_at = Some(Instant::now());
+                },
+                Some(signalled_at) if signalled_at.elapsed() >= deadline.kill_after() => {
+                    debug!("Process {} outlived the grace period, killing it", child.id());
+                    let _ = child.kill();
+                    return Ok(wait_for_exit(child, true)?.expect("blocking wait should return an exit status"));
+                },
+                _ => {}
+            }
+            thread::sleep(DEADLINE_POLL_INTERVAL);
+        }
+    }
+
+    fn wait(&self, child: &mut Child, deadline: Option<&Deadline>) -> Result<(i32, ResourceUsage), CommandError> {
+        let (status, usage) = match deadline {
+            Some(deadline) => self.wait_until(child, deadline)?,
+            None => wait_for_exit(child, true)?.expect("blocking wait should return an exit status")
+        };
+        return Ok((exit_status_code(status), usage));
+    }
+}
+
+impl ProcessSpawner for SystemProcessSpawner {
+    fn run(&self, request: &ProcessRequest, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        if let Some(deadline) = request.deadline.as_ref().filter(|deadline| deadline.has_passed()) {
+            deadline.expire();
+            return Ok((TIMED_OUT_STATUS, String::new()));
+        }
+
+        let started_at = Instant::now();
+        let mut child = self.create_command(request)
+            .stdin(if input.is_some() { Stdio::piped() } else { Stdio::inherit() })
+            .stdout(if capture_output { Stdio::piped() } else { Stdio::inherit() })
+            .spawn()
+            .map_err(could_not_execute)?;
+
+        let writer = child.stdin.take().map(|mut stdin| {
+            let input = input.unwrap_or_default().to_string();
+            thread::spawn(move || {
+                let _ = stdin.write_all(input.as_bytes());
+            })
+        });
+        let reader = child.stdout.take().map(|mut stdout| {
+            thread::spawn(move || {
+                let mut output = Vec::<u8>::new();
+                let _ = stdout.read_to_end(&mut output);
+                output
+            })
+        });
+
+        let (exit_code, usage) = self.wait(&mut child, request.deadline.as_ref())?;
+        request.record_usage(exit_code, started_at.elapsed(), usage);
+
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+        let output = match reader {
+            Some(reader) => reader.join().unwrap_or_default(),
+            None => Vec::new()
+        };
+        return Ok((exit_code, String::from_utf8_lossy(&output).into_owned()));
+    }
+}
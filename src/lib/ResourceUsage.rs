@@ -0,0 +1,190 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+/// Resources consumed by one or more processes, as reported by `rusage`.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceUsage {
+	pub user_time: Duration,
+	pub system_time: Duration,
+	pub max_rss_kib: u64,
+	pub voluntary_context_switches: u64,
+	pub involuntary_context_switches: u64
+}
+
+impl ResourceUsage {
+	pub fn new() -> ResourceUsage {
+		return ResourceUsage::default();
+	}
+
+	pub fn add(&mut self, other: &ResourceUsage) {
+		self.user_time += other.user_time;
+		self.system_time += other.system_time;
+		self.max_rss_kib = self.max_rss_kib.max(other.max_rss_kib);
+		self.voluntary_context_switches += other.voluntary_context_switches;
+		self.involuntary_context_switches += other.involuntary_context_switches;
+	}
+}
+
+/// The usage of a single finished process.
+#[derive(Debug, Clone)]
+pub struct ProcessUsage {
+	pub program: String,
+	pub exit_code: i32,
+	pub wall_time: Duration,
+	pub usage: ResourceUsage
+}
+
+/// Collects the usage of every process started while it is active in the shell state.
+#[derive(Debug, Clone, Default)]
+pub struct UsageRecorder {
+	processes: Rc<RefCell<Vec<ProcessUsage>>>
+}
+
+impl UsageRecorder {
+	pub fn new() -> UsageRecorder {
+		return UsageRecorder::default();
+	}
+
+	pub fn record(&self, process: ProcessUsage) {
+		self.processes.borrow_mut().push(process);
+	}
+
+	pub fn processes(&self) -> Vec<ProcessUsage> {
+		return self.processes.borrow().clone();
+	}
+}
+
+pub struct TimeReport {
+	pub wall_time: Duration,
+	pub exit_code: i32,
+	pub processes: Vec<ProcessUsage>
+}
+
+fn format_clock(duration: Duration) -> String {
+	let millis = duration.as_millis();
+	return format!("{}m{}.{:03}s", millis / 60000, millis / 1000 % 60, millis % 1000);
+}
+
+fn format_seconds(duration: Duration) -> String {
+	return format!("{:.3}", duration.as_secs_f64());
+}
+
+fn json_string(text: &str) -> String {
+	let mut escaped = String::from("\"");
+	for c in text.chars() {
+		match c {
+			'"' => escaped.push_str("\\\""),
+			'\\' => escaped.push_str("\\\\"),
+			'\n' => escaped.push_str("\\n"),
+			'\t' => escaped.push_str("\\t"),
+			c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+			c => escaped.push(c)
+		}
+	}
+	escaped.push('"');
+	return escaped;
+}
+
+fn json_usage_fields(wall_time: Duration, exit_code: i32, usage: &ResourceUsage) -> String {
+	return format!(
+		"\"real\":{},\"user\":{},\"sys\":{},\"max_rss_kib\":{},\"voluntary_context_switches\":{},\"involuntary_context_switches\":{},\"status\":{}",
+		format_seconds(wall_time),
+		format_seconds(usage.user_time),
+		format_seconds(usage.system_time),
+		usage.max_rss_kib,
+		usage.voluntary_context_switches,
+		usage.involuntary_context_switches,
+		exit_code
+	);
+}
+
+impl TimeReport {
+	pub fn total(&self) -> ResourceUsage {
+		let mut total = ResourceUsage::new();
+		for process in &self.processes {
+			total.add(&process.usage);
+		}
+		return total;
+	}
+
+	pub fn format_text(&self) -> String {
+		let total = self.total();
+		let mut text = format!(
+			"real\t{}\nuser\t{}\nsys\t{}\nmaxrss\t{}KiB\nctxsw\t{} voluntary, {} involuntary\n",
+			format_clock(self.wall_time),
+			format_clock(total.user_time),
+			format_clock(total.system_time),
+			total.max_rss_kib,
+			total.voluntary_context_switches,
+			total.involuntary_context_switches
+		);
+
+		if self.processes.len() > 1 {
+			for (i, process) in self.processes.iter().enumerate() {
+				text.push_str(&format!(
+					"#{} {}\treal {}  user {}  sys {}  maxrss {}KiB  ctxsw {}/{}  status {}\n",
+					i + 1,
+					process.program,
+					format_clock(process.wall_time),
+					format_clock(process.usage.user_time),
+					format_clock(process.usage.system_time),
+					process.usage.max_rss_kib,
+					process.usage.voluntary_context_switches,
+					process.usage.involuntary_context_switches,
+					process.exit_code
+				));
+			}
+		}
+		return text;
+	}
+
+	pub fn format_json(&self) -> String {
+		let stages: Vec<String> = self.processes.iter()
+			.map(|process| format!(
+				"{{\"program\":{},{}}}",
+				json_string(&process.program),
+				json_usage_fields(process.wall_time, process.exit_code, &process.usage)
+			))
+			.collect();
+
+		return format!(
+			"{{{},\"stages\":[{}]}}\n",
+			json_usage_fields(self.wall_time, self.exit_code, &self.total()),
+			stages.join(",")
+		);
+	}
+
+	// Expands a TIMEFORMAT style template. `%R`, `%U` and `%S` are seconds, `%M` is the maximum
+	// resident set size in KiB, `%w` and `%c` are voluntary and involuntary context switches,
+	// `%x` is the exit status and `%n` the number of processes.
+	pub fn format_template(&self, template: &str) -> String {
+		let total = self.total();
+		let mut text = String::new();
+		let mut chars = template.chars();
+		while let Some(c) = chars.next() {
+			if c != '%' {
+				text.push(c);
+				continue;
+			}
+			match chars.next() {
+				Some('R') => text.push_str(&format_seconds(self.wall_time)),
+				Some('U') => text.push_str(&format_seconds(total.user_time)),
+				Some('S') => text.push_str(&format_seconds(total.system_time)),
+				Some('M') => text.push_str(&total.max_rss_kib.to_string()),
+				Some('w') => text.push_str(&total.voluntary_context_switches.to_string()),
+				Some('c') => text.push_str(&total.involuntary_context_switches.to_string()),
+				Some('x') => text.push_str(&self.exit_code.to_string()),
+				Some('n') => text.push_str(&self.processes.len().to_string()),
+				Some('%') => text.push('%'),
+				Some(other) => {
+					text.push('%');
+					text.push(other);
+				},
+				None => text.push('%')
+			}
+		}
+		text.push('\n');
+		return text;
+	}
+}
@@ -0,0 +1,246 @@
+use std::cell::RefCell;
+use std::ffi::{OsStr, OsString};
+use std::fs::Metadata;
+use std::path::{Component, Path, PathBuf};
+use log::{debug, trace};
+use crate::Command::CommandError;
+use crate::PathResolver::{CommandMatch, PathResolver};
+
+/// A file found by a [`FileSystemProbe`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct FoundFile {
+	/// The path the file should be run as. Symbolic links are kept so that programs see the name
+	/// they were started by.
+	pub path: OsString,
+	pub executable: bool
+}
+
+/// Answers whether a file exists, so that command lookup can run against something other than the
+/// real filesystem.
+pub trait FileSystemProbe {
+	/// Looks for `file_name` in `directory`. `case_sensitive` tells probes that compare names
+	/// themselves how to compare them.
+	fn find_file(&self, directory: &OsStr, file_name: &OsStr, case_sensitive: bool) -> Option<FoundFile>;
+}
+
+/// Probes the real filesystem. Case sensitivity is whatever the filesystem does.
+pub struct SystemFileSystem {
+
+}
+
+impl SystemFileSystem {
+	pub fn new() -> SystemFileSystem {
+		return SystemFileSystem {};
+	}
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &Metadata) -> bool {
+	use std::os::unix::fs::PermissionsExt;
+	return metadata.permissions().mode() & 0o111 != 0;
+}
+
+// Windows decides what can run by extension, which the resolver already handles.
+#[cfg(not(unix))]
+fn is_executable(_metadata: &Metadata) -> bool {
+	return true;
+}
+
+impl FileSystemProbe for SystemFileSystem {
+	fn find_file(&self, directory: &OsStr, file_name: &OsStr, _case_sensitive: bool) -> Option<FoundFile> {
+		let path = Path::new(directory).join(file_name);
+		trace!("Trying {:?}", path);
+		let metadata = path.metadata().ok().filter(|metadata| metadata.is_file())?;
+		return Some(FoundFile {
+			path: path.into_os_string(),
+			executable: is_executable(&metadata)
+		});
+	}
+}
+
+/// Splits a search path variable such as `PATH` on `separator`, dropping empty entries.
+pub fn split_search_path(search_path: &OsStr, separator: char) -> Vec<OsString> {
+	let mut buffer = [0; 4];
+	let separator = separator.encode_utf8(&mut buffer).as_bytes();
+	let bytes = search_path.as_encoded_bytes();
+
+	let mut directories = Vec::new();
+	let mut start = 0;
+	let mut index = 0;
+	while index <= bytes.len() {
+		if index == bytes.len() || bytes[index..].starts_with(separator) {
+			if start < index {
+				// SAFETY: the slice starts and ends at a UTF-8 separator or at the ends of the string.
+				let directory = unsafe { OsStr::from_encoded_bytes_unchecked(&bytes[start..index]) };
+				directories.push(directory.to_os_string());
+			}
+			index += separator.len();
+			start = index;
+		} else {
+			index += 1;
+		}
+	}
+	return directories;
+}
+
+/// Drops `.` components and resolves `..` against the component before it, without looking at the
+/// filesystem, so that symbolic links in the path are kept.
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+	let mut normalized = PathBuf::new();
+	for component in path.components() {
+		match component {
+			Component::CurDir => {},
+			Component::ParentDir if matches!(normalized.components().next_back(), Some(Component::Normal(_))) => {
+				normalized.pop();
+			},
+			// `..` at the root stays at the root.
+			Component::ParentDir if normalized.has_root() => {},
+			component => normalized.push(component)
+		}
+	}
+	return normalized;
+}
+
+fn ends_with(name: &OsStr, suffix: &OsStr, case_sensitive: bool) -> bool {
+	let name = name.as_encoded_bytes();
+	let suffix = suffix.as_encoded_bytes();
+	if suffix.len() > name.len() {
+		return false;
+	}
+	let tail = &name[name.len() - suffix.len()..];
+	return if case_sensitive { tail == suffix } else { tail.eq_ignore_ascii_case(suffix) };
+}
+
+/// Looks commands up in a list of directories, trying each extension in turn.
+///
+/// The platform resolvers are configurations of this one: see `nt::WindowsPathResolver` and
+/// `unix::UnixPathResolver`.
+pub struct SearchPathResolver {
+	search_path: RefCell<Vec<OsString>>,
+	separator: char,
+	extensions: Vec<OsString>,
+	case_sensitive: bool,
+	probe: Box<dyn FileSystemProbe>
+}
+
+impl SearchPathResolver {
+	/// `extensions` are tried in order for every directory. Include an empty extension to also
+	/// try the bare name. A name that already ends in one of the extensions is tried as is first.
+	pub fn new(
+		search_path: &OsStr,
+		separator: char,
+		extensions: Vec<OsString>,
+		case_sensitive: bool,
+		probe: Box<dyn FileSystemProbe>
+	) -> SearchPathResolver {
+		return SearchPathResolver {
+			search_path: RefCell::new(split_search_path(search_path, separator)),
+			separator: separator,
+			extensions: extensions,
+			case_sensitive: case_sensitive,
+			probe: probe
+		};
+	}
+
+	pub fn search_path(&self) -> Vec<OsString> {
+		return self.search_path.borrow().clone();
+	}
+
+	fn extensions_for(&self, command_name: &str) -> Vec<&OsString> {
+		let has_extension = self.extensions.iter()
+			.any(|extension| !extension.is_empty() && ends_with(OsStr::new(command_name), extension, self.case_sensitive));
+
+		let mut extensions: Vec<&OsString> = self.extensions.iter().collect();
+		if has_extension {
+			extensions.sort_by_key(|extension| !extension.is_empty());
+		}
+		return extensions;
+	}
+
+	// Every file in `directory` the command name could refer to, executable or not.
+	fn look_in_directory(&self, directory: &OsStr, command_name: &str) -> Vec<(FoundFile, OsString)> {
+		let mut found = Vec::new();
+		for extension in self.extensions_for(command_name) {
+			let mut file_name = OsString::from(command_name);
+			file_name.push(extension);
+			if let Some(file) = self.probe.find_file(directory, &file_name, self.case_sensitive) {
+				found.push((file, extension.clone()));
+			}
+		}
+		return found;
+	}
+
+	fn executables_in_directory(&self, directory: &OsStr, command_name: &str) -> Vec<CommandMatch> {
+		return self.look_in_directory(directory, command_name).into_iter()
+			.filter(|(file, _)| file.executable)
+			.map(|(file, extension)| CommandMatch {
+				path: file.path,
+				extension: extension
+			})
+			.collect();
+	}
+}
+
+impl PathResolver for SearchPathResolver {
+	fn resolve_command_global(&self, command_name: &str) -> Result<OsString, CommandError> {
+		let search_path = self.search_path.borrow();
+		let found = search_path.iter()
+			.find_map(|directory| self.executables_in_directory(directory, command_name).into_iter().next());
+
+		return match found {
+			Some(command_match) => {
+				debug!("Resolved {} to {:?}", command_name, command_match.path);
+				Ok(command_match.path)
+			},
+			None => {
+				debug!("Could not resolve {} in {} path directories", command_name, search_path.len());
+				Err(CommandError::CouldNotExecute {
+					reason: format!("{}: command not found", command_name)
+				})
+			}
+		};
+	}
+
+	fn resolve_command_local(&self, current_directory: &Path, given_path: &str) -> Result<OsString, CommandError> {
+		let path = normalize_lexically(&current_directory.join(given_path));
+		let (Some(directory), Some(file_name)) = (path.parent(), path.file_name()) else {
+			return Err(CommandError::CouldNotExecute {
+				reason: format!("{}: not a file", given_path)
+			});
+		};
+
+		let found = self.look_in_directory(directory.as_os_str(), &file_name.to_string_lossy());
+		if let Some((file, _)) = found.iter().find(|(file, _)| file.executable) {
+			debug!("Resolved {} to {:?}", given_path, file.path);
+			return Ok(file.path.clone());
+		}
+
+		if found.is_empty() {
+			return Err(CommandError::CouldNotExecute {
+				reason: format!("{}: no such file", given_path)
+			});
+		}
+		return Err(CommandError::NotExecutable {
+			reason: format!("{}: permission denied", given_path)
+		});
+	}
+
+	fn resolve_command_all(&self, command_name: &str) -> Vec<CommandMatch> {
+		// A directory listed twice in the search path would otherwise report the same file twice.
+		let mut matches = Vec::<CommandMatch>::new();
+		for directory in self.search_path.borrow().iter() {
+			for command_match in self.executables_in_directory(directory, command_name) {
+				if !matches.iter().any(|existing| existing.path == command_match.path) {
+					matches.push(command_match);
+				}
+			}
+		}
+		return matches;
+	}
+
+	fn set_search_path(&self, search_path: &OsStr) {
+		let directories = split_search_path(search_path, self.separator);
+		debug!("Search path changed to {} directories", directories.len());
+		*self.search_path.borrow_mut() = directories;
+	}
+}
@@ -0,0 +1,330 @@
+//! High level entry point for embedding Jomsole.
+//!
+//! A [`Shell`] bundles a command parser, a [`CommandInterface`], a [`PathResolver`], the builtin
+//! registry and the shared [`ShellState`]. It is created through [`ShellBuilder`]:
+//!
+//! ```no_run
+//! use jomsole::Shell::Shell;
+//! use jomsole::ShellState::Variable;
+//!
+//! let shell = Shell::builder().build().unwrap();
+//! shell.set_variable("greeting", Variable::Scalar("hello".to_string()));
+//! let status = shell.eval("echo $greeting");
+//! assert_eq!(status, 0);
+//! ```
+
+use std::cell::RefCell;
+use std::io::Cursor;
+use std::rc::Rc;
+use log::debug;
+
+use crate::Builtin::{Builtin, BuiltinRegistry};
+use crate::Builtins::HashBuiltin::HashBuiltin;
+use crate::Builtins::TypeBuiltin::TypeBuiltin;
+use crate::CachingPathResolver::CachingPathResolver;
+use crate::Command::Command;
+use crate::CommandInterface::{CommandInterface, Ecma48CommandInterface, ScriptCommandInterface};
+use crate::CommandParser::{CommandParser, CommandParserError};
+use crate::Commands::SourceCommand::SourceCommand;
+use crate::Jomsole::Jomsole;
+use crate::PathResolver::PathResolver;
+use crate::ProcessSpawner::{ProcessSpawner, SystemProcessSpawner};
+use crate::ShellState::{ShellOptions, ShellState, Variable};
+use crate::StartupFiles::StartupFiles;
+use crate::jsh::JshCommandParser::JshCommandParser;
+
+/// The shared pieces a command parser needs to lower commands for a [`Shell`].
+pub struct ShellContext {
+	pub path_resolver: Rc<dyn PathResolver>,
+	pub process_spawner: Rc<dyn ProcessSpawner>,
+	pub state: Rc<RefCell<ShellState>>,
+	pub builtins: Rc<BuiltinRegistry>
+}
+
+/// A parser error with the concrete error type of the underlying parser erased.
+#[derive(Debug, Clone)]
+pub struct ShellParseError {
+	reason: String,
+	incomplete: bool
+}
+
+impl CommandParserError for ShellParseError {
+	fn describe(&self) -> String {
+		return self.reason.clone();
+	}
+
+	fn is_incomplete(&self) -> bool {
+		return self.incomplete;
+	}
+}
+
+struct ErasedCommandParser<TCommandParser: CommandParser> {
+	inner: TCommandParser
+}
+
+impl<TCommandParser: CommandParser> CommandParser for ErasedCommandParser<TCommandParser> {
+	type TCommandParserError = ShellParseError;
+
+	fn parse_command(&self, command: &str) -> Result<Option<Box<dyn Command>>, ShellParseError> {
+		return self.inner.parse_command(command).map_err(|err| ShellParseError {
+			reason: err.describe(),
+			incomplete: err.is_incomplete()
+		});
+	}
+}
+
+type BoxedCommandParser = Box<dyn CommandParser<TCommandParserError = ShellParseError>>;
+type CommandParserFactory = Box<dyn FnOnce(&ShellContext) -> BoxedCommandParser>;
+
+/// Errors that can occur while building a [`Shell`].
+#[derive(Debug, Clone)]
+pub enum ShellBuildError {
+	/// No path resolver was given and there is no default one for this platform.
+	MissingPathResolver
+}
+
+impl ShellBuildError {
+	pub fn describe(&self) -> String {
+		return match self {
+			ShellBuildError::MissingPathResolver => "no path resolver is available for this platform".to_string()
+		};
+	}
+}
+
+#[cfg(windows)]
+fn default_path_resolver() -> Option<Rc<dyn PathResolver>> {
+	return Some(Rc::new(crate::nt::WindowsPathResolver::new()));
+}
+
+#[cfg(unix)]
+fn default_path_resolver() -> Option<Rc<dyn PathResolver>> {
+	return Some(Rc::new(crate::unix::UnixPathResolver::new()));
+}
+
+#[cfg(not(any(windows, unix)))]
+fn default_path_resolver() -> Option<Rc<dyn PathResolver>> {
+	return None;
+}
+
+/// Configures and creates a [`Shell`].
+///
+/// Every part has a default: the JomsaSH parser, an interactive terminal interface, the
+/// platform path resolver, real operating system processes and the default builtins.
+pub struct ShellBuilder {
+	path_resolver: Option<Rc<dyn PathResolver>>,
+	process_spawner: Option<Rc<dyn ProcessSpawner>>,
+	command_interface: Option<Box<dyn CommandInterface>>,
+	parser_factory: Option<CommandParserFactory>,
+	builtins: BuiltinRegistry,
+	options: ShellOptions,
+	interactive: bool
+}
+
+impl ShellBuilder {
+	pub fn new() -> ShellBuilder {
+		return ShellBuilder {
+			path_resolver: None,
+			process_spawner: None,
+			command_interface: None,
+			parser_factory: None,
+			builtins: BuiltinRegistry::with_defaults(),
+			options: ShellOptions::new(),
+			interactive: true
+		};
+	}
+
+	/// Sets the resolver used to turn command names into executable paths.
+	pub fn path_resolver(mut self, path_resolver: Rc<dyn PathResolver>) -> ShellBuilder {
+		self.path_resolver = Some(path_resolver);
+		return self;
+	}
+
+	/// Sets how external programs are started.
+	pub fn process_spawner(mut self, process_spawner: Rc<dyn ProcessSpawner>) -> ShellBuilder {
+		self.process_spawner = Some(process_spawner);
+		return self;
+	}
+
+	/// Sets where command lines are read from when the shell is [run](Shell::run).
+	pub fn command_interface<TCommandInterface: CommandInterface + 'static>(
+		mut self,
+		command_interface: TCommandInterface
+	) -> ShellBuilder {
+		self.command_interface = Some(Box::new(command_interface));
+		return self;
+	}
+
+	/// Replaces the JomsaSH parser. The factory is called once during [`build`](ShellBuilder::build).
+	pub fn parser<TCommandParser, TFactory>(mut self, factory: TFactory) -> ShellBuilder
+	where
+		TCommandParser: CommandParser + 'static,
+		TFactory: FnOnce(&ShellContext) -> TCommandParser + 'static
+	{
+		self.parser_factory = Some(Box::new(move |context: &ShellContext| -> BoxedCommandParser {
+			return Box::new(ErasedCommandParser { inner: factory(context) });
+		}));
+		return self;
+	}
+
+	/// Replaces the whole builtin registry, including the defaults.
+	pub fn builtins(mut self, builtins: BuiltinRegistry) -> ShellBuilder {
+		self.builtins = builtins;
+		return self;
+	}
+
+	/// Registers a single builtin, overriding any builtin with the same name.
+	pub fn builtin(mut self, name: &str, builtin: Rc<dyn Builtin>) -> ShellBuilder {
+		self.builtins.register(name, builtin);
+		return self;
+	}
+
+	/// Sets the initial shell options, such as `xtrace`.
+	pub fn options(mut self, options: ShellOptions) -> ShellBuilder {
+		self.options = options;
+		return self;
+	}
+
+	/// Interactive shells print errors to stdout and report the exit code of every command.
+	pub fn interactive(mut self, interactive: bool) -> ShellBuilder {
+		self.interactive = interactive;
+		return self;
+	}
+
+	pub fn build(self) -> Result<Shell, ShellBuildError> {
+		let path_resolver = match self.path_resolver {
+			Some(path_resolver) => path_resolver,
+			None => default_path_resolver().ok_or(ShellBuildError::MissingPathResolver)?
+		};
+
+		let mut state = ShellState::new();
+		state.options = self.options;
+		let state = Rc::new(RefCell::new(state));
+
+		// `hash`, `type` and `which` look commands up through the uncached resolver so that they always
+		// see the current search path.
+		let mut builtins = self.builtins;
+		if builtins.get("hash").is_none() {
+			builtins.register("hash", Rc::new(HashBuiltin::new(path_resolver.clone())));
+		}
+		let mut builtin_names = builtins.names();
+		builtin_names.extend(["type".to_string(), "which".to_string()]);
+		for name in ["type", "which"] {
+			if builtins.get(name).is_none() {
+				builtins.register(name, Rc::new(TypeBuiltin::new(name, path_resolver.clone(), builtin_names.clone())));
+			}
+		}
+
+		let context = ShellContext {
+			path_resolver: Rc::new(CachingPathResolver::new(path_resolver, state.clone())),
+			process_spawner: self.process_spawner.unwrap_or_else(|| Rc::new(SystemProcessSpawner::new())),
+			state: state,
+			builtins: Rc::new(builtins)
+		};
+
+		let parser = match self.parser_factory {
+			Some(factory) => factory(&context),
+			None => Box::new(ErasedCommandParser {
+				inner: JshCommandParser::new(
+					context.path_resolver.clone(),
+					context.process_spawner.clone(),
+					context.state.clone(),
+					context.builtins.clone()
+				)
+			})
+		};
+
+		return Ok(Shell {
+			parser: parser,
+			command_interface: self.command_interface.unwrap_or_else(|| Box::new(Ecma48CommandInterface::new())),
+			context: context,
+			interactive: self.interactive
+		});
+	}
+}
+
+/// A configured shell instance.
+pub struct Shell {
+	parser: BoxedCommandParser,
+	command_interface: Box<dyn CommandInterface>,
+	context: ShellContext,
+	interactive: bool
+}
+
+impl Shell {
+	pub fn builder() -> ShellBuilder {
+		return ShellBuilder::new();
+	}
+
+	/// Reads and executes commands from the command interface until it runs out of input.
+	/// Returns the status of the last command.
+	pub fn run(&self) -> i32 {
+		let jomsole = Jomsole::new(
+			&self.parser,
+			&self.command_interface,
+			self.context.path_resolver.clone(),
+			self.interactive,
+			self.context.state.clone()
+		);
+		return jomsole.run();
+	}
+
+	/// Executes `source` as a non-interactive script and returns the status of its last command.
+	/// Variables and options set by the script stay visible to later calls.
+	pub fn eval(&self, source: &str) -> i32 {
+		let jomsole = Jomsole::new(
+			&self.parser,
+			ScriptCommandInterface::new(Cursor::new(source.to_string())),
+			self.context.path_resolver.clone(),
+			false,
+			self.context.state.clone()
+		);
+		return jomsole.run();
+	}
+
+	/// Runs the profile and rc files for this session in the current shell state. Errors in a file are
+	/// reported with its name and line, and startup carries on with the next command or file.
+	pub fn run_startup_files(&self, startup_files: &StartupFiles) {
+		let paths = startup_files.paths(&self.context.state.borrow());
+		let source = SourceCommand::new(
+			Vec::new(),
+			self.context.path_resolver.clone(),
+			self.context.process_spawner.clone(),
+			self.context.state.clone(),
+			self.context.builtins.clone()
+		);
+		for path in paths {
+			if !path.is_file() {
+				eprintln!("jomsole: {}: file not found", path.display());
+				continue;
+			}
+			debug!("Running startup file {:?}", path);
+			source.source_path(&path, Vec::new(), None, false);
+		}
+	}
+
+	pub fn get_variable(&self, name: &str) -> Option<Variable> {
+		return self.context.state.borrow().get_variable(name).cloned();
+	}
+
+	pub fn set_variable(&self, name: &str, value: Variable) {
+		self.context.state.borrow_mut().set_variable(name, value);
+	}
+
+	/// The status of the most recently finished pipeline.
+	pub fn last_status(&self) -> i32 {
+		return self.context.state.borrow().last_status();
+	}
+
+	/// The state shared by every command this shell runs.
+	pub fn state(&self) -> Rc<RefCell<ShellState>> {
+		return self.context.state.clone();
+	}
+
+	pub fn path_resolver(&self) -> Rc<dyn PathResolver> {
+		return self.context.path_resolver.clone();
+	}
+
+	pub fn builtins(&self) -> Rc<BuiltinRegistry> {
+		return self.context.builtins.clone();
+	}
+}
@@ -0,0 +1,226 @@
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::CachingPathResolver::{CommandHash, SEARCH_PATH_VARIABLES};
+use crate::Deadline::Deadline;
+use crate::ProcessAttributes::ProcessAttributes;
+use crate::ResourceUsage::UsageRecorder;
+
+#[derive(Debug, Clone)]
+pub enum Variable {
+	Scalar(String),
+	Array(Vec<String>)
+}
+
+#[derive(Debug, Clone)]
+pub struct ShellOptions {
+	pub pipefail: bool,
+	pub xtrace: bool,
+	pub verbose: bool,
+	pub command_timeout: Option<Duration>
+}
+
+impl ShellOptions {
+	pub fn new() -> ShellOptions {
+		return ShellOptions {
+			pipefail: false,
+			xtrace: false,
+			verbose: false,
+			command_timeout: None
+		};
+	}
+
+	pub fn apply_flag(&mut self, flag: &str) -> bool {
+		let enable = match flag.chars().next() {
+			Some('-') => true,
+			Some('+') => false,
+			_ => return false
+		};
+		let letters = &flag[1..];
+		if letters.is_empty() || !letters.chars().all(|letter| letter == 'x' || letter == 'v') {
+			return false;
+		}
+
+		for letter in letters.chars() {
+			match letter {
+				'x' => self.xtrace = enable,
+				_ => self.verbose = enable
+			}
+		}
+		return true;
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct ShellState {
+	variables: HashMap<String, Variable>,
+	last_status: i32,
+	trace_redirections: Vec<String>,
+	deadlines: Vec<Deadline>,
+	usage_recorders: Vec<UsageRecorder>,
+	source_files: Vec<PathBuf>,
+	source_lines: Vec<usize>,
+	included_files: HashSet<PathBuf>,
+	script_name: String,
+	positional_parameters: Vec<String>,
+	current_directory: PathBuf,
+	previous_directory: Option<PathBuf>,
+	/// Directories saved by `pushd`, most recent first. The current directory is not included.
+	pub directory_stack: Vec<PathBuf>,
+	pub options: ShellOptions,
+	pub process_attributes: ProcessAttributes,
+	pub command_hash: CommandHash
+}
+
+impl ShellState {
+	pub fn new() -> ShellState {
+		return ShellState {
+			variables: HashMap::new(),
+			last_status: 0,
+			trace_redirections: Vec::new(),
+			deadlines: Vec::new(),
+			usage_recorders: Vec::new(),
+			source_files: Vec::new(),
+			source_lines: Vec::new(),
+			included_files: HashSet::new(),
+			script_name: "jomsole".to_string(),
+			positional_parameters: Vec::new(),
+			current_directory: env::current_dir().unwrap_or_default(),
+			previous_directory: None,
+			directory_stack: Vec::new(),
+			options: ShellOptions::new(),
+			process_attributes: ProcessAttributes::new(),
+			command_hash: CommandHash::new()
+		};
+	}
+
+	pub fn get_variable(&self, name: &str) -> Option<&Variable> {
+		return self.variables.get(name);
+	}
+
+	pub fn set_variable(&mut self, name: &str, value: Variable) {
+		if SEARCH_PATH_VARIABLES.contains(&name) {
+			self.command_hash.clear();
+		}
+		self.variables.insert(name.to_string(), value);
+	}
+
+	pub fn last_status(&self) -> i32 {
+		return self.last_status;
+	}
+
+	pub fn set_last_status(&mut self, status: i32) {
+		self.last_status = status;
+	}
+
+	pub fn push_trace_redirection(&mut self, redirection: String) {
+		self.trace_redirections.push(redirection);
+	}
+
+	pub fn pop_trace_redirection(&mut self) {
+		self.trace_redirections.pop();
+	}
+
+	pub fn trace_redirections(&self) -> &[String] {
+		return &self.trace_redirections;
+	}
+
+	pub fn push_deadline(&mut self, deadline: Deadline) {
+		self.deadlines.push(deadline);
+	}
+
+	pub fn pop_deadline(&mut self) {
+		self.deadlines.pop();
+	}
+
+	pub fn deadline(&self) -> Option<&Deadline> {
+		return self.deadlines.iter().min_by_key(|deadline| deadline.expires_at());
+	}
+
+	pub fn push_usage_recorder(&mut self, recorder: UsageRecorder) {
+		self.usage_recorders.push(recorder);
+	}
+
+	pub fn pop_usage_recorder(&mut self) {
+		self.usage_recorders.pop();
+	}
+
+	pub fn usage_recorders(&self) -> &[UsageRecorder] {
+		return &self.usage_recorders;
+	}
+
+	pub fn push_source_file(&mut self, path: PathBuf) {
+		self.source_files.push(path);
+		self.source_lines.push(0);
+	}
+
+	pub fn pop_source_file(&mut self) {
+		self.source_files.pop();
+		self.source_lines.pop();
+	}
+
+	/// Records the line of the command the innermost sourced file is running.
+	pub fn set_source_line(&mut self, line: usize) {
+		if let Some(current_line) = self.source_lines.last_mut() {
+			*current_line = line;
+		}
+	}
+
+	/// `FILE:LINE` of the command the innermost sourced file is running, for error messages.
+	pub fn source_location(&self) -> Option<String> {
+		return self.source_files.last()
+			.zip(self.source_lines.last())
+			.map(|(path, line)| format!("{}:{}", path.display(), line));
+	}
+
+	/// The files `source` is currently running, outermost first.
+	pub fn source_files(&self) -> &[PathBuf] {
+		return &self.source_files;
+	}
+
+	/// Records that `include` ran `path`, returning false if it already had.
+	pub fn mark_included(&mut self, path: PathBuf) -> bool {
+		return self.included_files.insert(path);
+	}
+
+	/// `$0`
+	pub fn script_name(&self) -> &str {
+		return &self.script_name;
+	}
+
+	pub fn set_script_name(&mut self, name: &str) {
+		self.script_name = name.to_string();
+	}
+
+	/// `$1` onwards.
+	pub fn positional_parameters(&self) -> &[String] {
+		return &self.positional_parameters;
+	}
+
+	/// Replaces the positional parameters and returns the previous ones.
+	pub fn set_positional_parameters(&mut self, parameters: Vec<String>) -> Vec<String> {
+		return std::mem::replace(&mut self.positional_parameters, parameters);
+	}
+
+	/// The session's working directory. Children start in it and relative paths resolve against it.
+	pub fn current_directory(&self) -> &Path {
+		return &self.current_directory;
+	}
+
+	pub fn previous_directory(&self) -> Option<&Path> {
+		return self.previous_directory.as_deref();
+	}
+
+	/// Changes the working directory and updates `PWD` and `OLDPWD`. The caller checks that it exists.
+	pub fn set_current_directory(&mut self, directory: PathBuf) {
+		let previous = std::mem::replace(&mut self.current_directory, directory);
+		self.set_variable("OLDPWD", Variable::Scalar(previous.to_string_lossy().to_string()));
+		self.set_variable("PWD", Variable::Scalar(self.current_directory.to_string_lossy().to_string()));
+		self.previous_directory = Some(previous);
+	}
+
+	pub fn resolve_path(&self, path: &str) -> PathBuf {
+		return self.current_directory.join(path);
+	}
+}
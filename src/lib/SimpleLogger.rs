@@ -1,17 +1,127 @@
-use log::{Record, Level, Metadata};
+use std::fs::File;
+use std::io::{stderr, Write};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use log::{LevelFilter, Metadata, Record, SetLoggerError};
 
-pub struct SimpleLogger;
+pub enum LogTarget {
+    Stderr,
+    File(File)
+}
+
+pub struct SimpleLogger {
+    default_level: LevelFilter,
+    module_levels: Vec<(String, LevelFilter)>,
+    target: Mutex<LogTarget>
+}
+
+fn parse_level(text: &str) -> Result<LevelFilter, String> {
+    return text.trim().parse::<LevelFilter>()
+        .map_err(|_| format!("invalid log level `{}`", text.trim()));
+}
+
+fn module_matches(module: &str, target: &str) -> bool {
+    return target == module
+        || target.starts_with(&format!("{}::", module))
+        || target.ends_with(&format!("::{}", module))
+        || target.contains(&format!("::{}::", module));
+}
+
+fn days_to_date(days: u64) -> (u64, u64, u64) {
+    let days = days as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    return (year as u64, month as u64, day as u64);
+}
+
+fn format_timestamp(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let seconds = since_epoch.as_secs();
+    let (year, month, day) = days_to_date(seconds / 86400);
+    let seconds_of_day = seconds % 86400;
+    return format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60,
+        since_epoch.subsec_millis()
+    );
+}
+
+impl SimpleLogger {
+    pub fn new(spec: &str, target: LogTarget) -> Result<SimpleLogger, String> {
+        let mut default_level = LevelFilter::Off;
+        let mut module_levels = Vec::<(String, LevelFilter)>::new();
+
+        for directive in spec.split(',').filter(|directive| !directive.trim().is_empty()) {
+            match directive.split_once('=') {
+                Some((module, level)) => module_levels.push((module.trim().to_string(), parse_level(level)?)),
+                None => default_level = parse_level(directive)?
+            }
+        }
+        module_levels.sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+
+        return Ok(SimpleLogger {
+            default_level: default_level,
+            module_levels: module_levels,
+            target: Mutex::new(target)
+        });
+    }
+
+    pub fn install(self) -> Result<(), SetLoggerError> {
+        let max_level = self.module_levels.iter()
+            .map(|(_, level)| *level)
+            .fold(self.default_level, |max, level| max.max(level));
+
+        return log::set_logger(Box::leak(Box::new(self)))
+            .map(|()| log::set_max_level(max_level));
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        return self.module_levels.iter()
+            .find(|(module, _)| module_matches(module, target))
+            .map_or(self.default_level, |(_, level)| *level);
+    }
+}
 
 impl log::Log for SimpleLogger {
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= Level::Info
+        return metadata.level() <= self.level_for(metadata.target());
     }
 
     fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            println!("{} - {}", record.level(), record.args());
+        if !self.enabled(record.metadata()) {
+            return;
         }
+
+        let line = format!(
+            "{} {:<5} {} - {}\n",
+            format_timestamp(SystemTime::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        let mut target = self.target.lock().unwrap();
+        let _ = match &mut *target {
+            LogTarget::Stderr => stderr().write_all(line.as_bytes()),
+            LogTarget::File(file) => file.write_all(line.as_bytes())
+        };
     }
 
-    fn flush(&self) {}
-}
\ No newline at end of file
+    fn flush(&self) {
+        let mut target = self.target.lock().unwrap();
+        let _ = match &mut *target {
+            LogTarget::Stderr => stderr().flush(),
+            LogTarget::File(file) => file.flush()
+        };
+    }
+}
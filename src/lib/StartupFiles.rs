@@ -0,0 +1,97 @@
+//! Scripts a session runs before its first command.
+//!
+//! Login shells run the system-wide profile and then the user's profile. Interactive shells then
+//! run the system-wide rc and the user's rc. User files live in `$XDG_CONFIG_HOME/jomsole`
+//! (`~/.config/jomsole` when it is not set), with `~/.jomsole_profile` and `~/.jomsolerc` as
+//! fallbacks.
+
+use std::path::PathBuf;
+use crate::Builtins::CdBuiltin::{home_directory, lookup_variable};
+use crate::ShellState::ShellState;
+
+/// Which rc files an interactive session runs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RcFile {
+	/// The system-wide rc followed by the user's rc.
+	Default,
+	/// Only the given file, as with `--rcfile`.
+	Custom(PathBuf),
+	/// No rc files, as with `--norc`.
+	Disabled
+}
+
+#[derive(Debug, Clone)]
+pub struct StartupFiles {
+	login: bool,
+	interactive: bool,
+	rc_file: RcFile
+}
+
+#[cfg(windows)]
+fn system_directory() -> Option<PathBuf> {
+	return std::env::var_os("ProgramData").map(|directory| PathBuf::from(directory).join("jomsole"));
+}
+
+#[cfg(not(windows))]
+fn system_directory() -> Option<PathBuf> {
+	return Some(PathBuf::from("/etc"));
+}
+
+fn config_directory(state: &ShellState) -> Option<PathBuf> {
+	if let Some(directory) = lookup_variable(state, "XDG_CONFIG_HOME").filter(|directory| !directory.is_empty()) {
+		return Some(PathBuf::from(directory).join("jomsole"));
+	}
+	if cfg!(windows) {
+		if let Some(directory) = lookup_variable(state, "APPDATA") {
+			return Some(PathBuf::from(directory).join("jomsole"));
+		}
+	}
+	return home_directory(state).map(|home| PathBuf::from(home).join(".config").join("jomsole"));
+}
+
+// The file in the config directory, or the dotfile in the home directory when only that exists.
+fn user_file(state: &ShellState, name: &str, dotfile: &str) -> Option<PathBuf> {
+	let configured = config_directory(state).map(|directory| directory.join(name));
+	if configured.as_ref().is_some_and(|path| path.is_file()) {
+		return configured;
+	}
+	return home_directory(state)
+		.map(|home| PathBuf::from(home).join(dotfile))
+		.filter(|path| path.is_file());
+}
+
+impl StartupFiles {
+	pub fn new(login: bool, interactive: bool) -> StartupFiles {
+		return StartupFiles {
+			login: login,
+			interactive: interactive,
+			rc_file: RcFile::Default
+		};
+	}
+
+	pub fn rc_file(mut self, rc_file: RcFile) -> StartupFiles {
+		self.rc_file = rc_file;
+		return self;
+	}
+
+	/// The files to run, in order. Default locations that do not exist are left out; a file given
+	/// with `--rcfile` is always included so that a missing one gets reported.
+	pub fn paths(&self, state: &ShellState) -> Vec<PathBuf> {
+		let mut paths = Vec::new();
+		if self.login {
+			paths.extend(system_directory().map(|directory| directory.join("jomsole_profile")).filter(|path| path.is_file()));
+			paths.extend(user_file(state, "profile", ".jomsole_profile"));
+		}
+		if self.interactive {
+			match &self.rc_file {
+				RcFile::Default => {
+					paths.extend(system_directory().map(|directory| directory.join("jomsolerc")).filter(|path| path.is_file()));
+					paths.extend(user_file(state, "jomsolerc", ".jomsolerc"));
+				},
+				RcFile::Custom(path) => paths.push(state.current_directory().join(path)),
+				RcFile::Disabled => {}
+			}
+		}
+		return paths;
+	}
+}
@@ -0,0 +1,41 @@
+use crate::ShellState::{ShellState, Variable};
+
+const DEFAULT_TRACE_PREFIX: &str = "+ ";
+
+pub fn trace_quote(word: &str) -> String {
+	let plain = !word.is_empty() && word.chars()
+		.all(|c| c.is_ascii_alphanumeric() || "-_./:=@%+,^".contains(c));
+	if plain {
+		return word.to_string();
+	}
+	return format!("'{}'", word.replace('\'', "'\\''"));
+}
+
+fn trace_prefix(state: &ShellState) -> String {
+	return match state.get_variable("PS4") {
+		Some(Variable::Scalar(prefix)) => prefix.clone(),
+		Some(Variable::Array(elements)) => elements.first().cloned().unwrap_or_default(),
+		None => DEFAULT_TRACE_PREFIX.to_string()
+	};
+}
+
+pub fn trace_command(state: &ShellState, words: &[String]) {
+	let quoted: Vec<String> = words.iter()
+		.map(|word| trace_quote(word))
+		.collect();
+	trace_line(state, &quoted.join(" "));
+}
+
+pub fn trace_line(state: &ShellState, text: &str) {
+	if !state.options.xtrace {
+		return;
+	}
+
+	let mut line = trace_prefix(state);
+	line.push_str(text);
+	for redirection in state.trace_redirections() {
+		line.push(' ');
+		line.push_str(redirection);
+	}
+	eprintln!("{}", line);
+}
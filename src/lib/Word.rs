@@ -0,0 +1,150 @@
+use std::env;
+use crate::Command::CommandError;
+use crate::jsh::Arithmetic::ArithmeticExpression;
+use crate::ShellState::{ShellState, Variable};
+
+#[derive(Debug, Clone)]
+pub enum VariableSubscript {
+	None,
+	Index(usize),
+	All
+}
+
+#[derive(Debug, Clone)]
+pub enum WordSegment {
+	Literal(String),
+	Variable {
+		name: String,
+		subscript: VariableSubscript
+	},
+	LastStatus,
+	Positional(usize),
+	ParameterCount,
+	AllParameters,
+	Arithmetic {
+		expression: ArithmeticExpression,
+		source: String
+	},
+	/// Stands in for a word that cannot be expanded, such as a brace expansion over the word limit.
+	Unexpandable(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct Word {
+	segments: Vec<WordSegment>
+}
+
+impl Word {
+	pub fn new(segments: Vec<WordSegment>) -> Word {
+		return Word {
+			segments: segments
+		};
+	}
+
+	pub fn push(&mut self, segment: WordSegment) {
+		self.segments.push(segment);
+	}
+
+	pub fn as_literal(&self) -> Option<String> {
+		let mut text = String::new();
+		for segment in &self.segments {
+			match segment {
+				WordSegment::Literal(literal) => text.push_str(literal),
+				_ => return None
+			}
+		}
+		return Some(text);
+	}
+
+	pub fn expand(&self, state: &mut ShellState) -> Result<Vec<String>, CommandError> {
+		let mut fields = vec![String::new()];
+		let mut produced_anything = false;
+
+		for segment in &self.segments {
+			match segment {
+				WordSegment::Literal(literal) => {
+					fields.last_mut().unwrap().push_str(literal);
+					produced_anything = true;
+				},
+				WordSegment::LastStatus => {
+					fields.last_mut().unwrap().push_str(&state.last_status().to_string());
+					produced_anything = true;
+				},
+				WordSegment::Positional(index) => {
+					let value = match index {
+						0 => Some(state.script_name().to_string()),
+						index => state.positional_parameters().get(index - 1).cloned()
+					};
+					fields.last_mut().unwrap().push_str(&value.unwrap_or_default());
+					produced_anything = true;
+				},
+				WordSegment::ParameterCount => {
+					fields.last_mut().unwrap().push_str(&state.positional_parameters().len().to_string());
+					produced_anything = true;
+				},
+				WordSegment::AllParameters => {
+					for (i, parameter) in state.positional_parameters().iter().enumerate() {
+						if i > 0 {
+							fields.push(String::new());
+						}
+						fields.last_mut().unwrap().push_str(parameter);
+						produced_anything = true;
+					}
+				},
+				WordSegment::Arithmetic { expression, source } => {
+					let value = match expression.evaluate(state) {
+						Ok(value) => value,
+						Err(err) => return Err(CommandError::ExpansionFailed {
+							reason: err.describe(source)
+						})
+					};
+					fields.last_mut().unwrap().push_str(&value.to_string());
+					produced_anything = true;
+				},
+				WordSegment::Unexpandable(reason) => {
+					return Err(CommandError::ExpansionFailed {
+						reason: reason.clone()
+					});
+				},
+				WordSegment::Variable { name, subscript: VariableSubscript::All } => {
+					let elements = match state.get_variable(name) {
+						Some(Variable::Array(elements)) => elements.clone(),
+						Some(Variable::Scalar(value)) => vec![value.clone()],
+						None => env::var(name).into_iter().collect()
+					};
+					for (i, element) in elements.iter().enumerate() {
+						if i > 0 {
+							fields.push(String::new());
+						}
+						fields.last_mut().unwrap().push_str(element);
+						produced_anything = true;
+					}
+				},
+				WordSegment::Variable { name, subscript } => {
+					let index = match subscript {
+						VariableSubscript::Index(index) => *index,
+						_ => 0
+					};
+					let value = match state.get_variable(name) {
+						Some(Variable::Array(elements)) => elements.get(index).cloned(),
+						Some(Variable::Scalar(value)) if index == 0 => Some(value.clone()),
+						// Variables the shell has not set come from the environment it was started with.
+						None if index == 0 => env::var(name).ok(),
+						_ => None
+					};
+					fields.last_mut().unwrap().push_str(&value.unwrap_or_default());
+					produced_anything = true;
+				}
+			}
+		}
+
+		if !produced_anything {
+			return Ok(Vec::new());
+		}
+		return Ok(fields);
+	}
+
+	pub fn expand_to_string(&self, state: &mut ShellState) -> Result<String, CommandError> {
+		return Ok(self.expand(state)?.join(" "));
+	}
+}
@@ -0,0 +1,63 @@
+use std::ffi::{OsStr, OsString};
+use crate::SearchPathResolver::{FileSystemProbe, FoundFile};
+
+/// An in-memory set of files for testing lookup rules of other platforms.
+pub struct FakeFileSystem {
+    directory_separator: char,
+    files: Vec<FakeFile>
+}
+
+struct FakeFile {
+    directory: String,
+    file_name: String,
+    executable: bool
+}
+
+impl FakeFileSystem {
+    /// `directory_separator` splits the paths given to [`FakeFileSystem::add_file`], e.g. `\` for Windows.
+    pub fn new(directory_separator: char) -> FakeFileSystem {
+        return FakeFileSystem {
+            directory_separator: directory_separator,
+            files: Vec::new()
+        };
+    }
+
+    /// Adds an executable file.
+    pub fn add_file(&mut self, path: &str) {
+        self.add(path, true);
+    }
+
+    pub fn add_non_executable_file(&mut self, path: &str) {
+        self.add(path, false);
+    }
+
+    fn add(&mut self, path: &str, executable: bool) {
+        let (directory, file_name) = path.rsplit_once(self.directory_separator).unwrap_or(("", path));
+        self.files.push(FakeFile {
+            directory: directory.to_string(),
+            file_name: file_name.to_string(),
+            executable: executable
+        });
+    }
+}
+
+fn same_name(left: &str, right: &str, case_sensitive: bool) -> bool {
+    return if case_sensitive { left == right } else { left.eq_ignore_ascii_case(right) };
+}
+
+impl FileSystemProbe for FakeFileSystem {
+    fn find_file(&self, directory: &OsStr, file_name: &OsStr, case_sensitive: bool) -> Option<FoundFile> {
+        let directory = directory.to_string_lossy();
+        let directory = directory.trim_end_matches(self.directory_separator);
+        let file_name = file_name.to_string_lossy();
+
+        let file = self.files.iter().find(|file| {
+            same_name(&file.directory, directory, case_sensitive)
+                && same_name(&file.file_name, &file_name, case_sensitive)
+        })?;
+        return Some(FoundFile {
+            path: OsString::from(format!("{}{}{}", file.directory, self.directory_separator, file.file_name)),
+            executable: file.executable
+        });
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::Path;
+use crate::Command::CommandError;
+use crate::PathResolver::PathResolver;
+
+/// Resolves command names from a fixed table instead of searching the filesystem.
+pub struct FakePathResolver {
+    commands: HashMap<String, OsString>
+}
+
+impl FakePathResolver {
+    pub fn new() -> FakePathResolver {
+        return FakePathResolver {
+            commands: HashMap::new()
+        };
+    }
+
+    pub fn add_command(&mut self, command_name: &str, path: &str) {
+        self.commands.insert(command_name.to_string(), OsString::from(path));
+    }
+}
+
+impl PathResolver for FakePathResolver {
+    fn resolve_command_global(&self, command_name: &str) -> Result<OsString, CommandError> {
+        return match self.commands.get(command_name) {
+            Some(path) => Ok(path.clone()),
+            None => Err(CommandError::CouldNotExecute {
+                reason: format!("{}: command not found", command_name)
+            })
+        };
+    }
+
+    fn resolve_command_local(&self, current_directory: &Path, given_path: &str) -> Result<OsString, CommandError> {
+        return Ok(current_directory
+            .join(given_path)
+            .as_os_str().to_os_string());
+    }
+}
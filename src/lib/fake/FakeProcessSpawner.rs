@@ -0,0 +1,72 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Write;
+use std::rc::Rc;
+use std::time::Instant;
+use crate::Command::CommandError;
+use crate::ProcessSpawner::{ProcessRequest, ProcessSpawner};
+use crate::ResourceUsage::ResourceUsage;
+
+/// A fake program receives its arguments and stdin, writes its stdout and returns its exit code.
+pub type FakeProgram = Rc<dyn Fn(&[String], &str, &mut dyn Write) -> i32>;
+
+/// Runs registered closures instead of real processes.
+///
+/// Output that is not captured by the shell, which a real process would write to the terminal,
+/// is collected and can be read with [`take_output`](FakeProcessSpawner::take_output).
+pub struct FakeProcessSpawner {
+    programs: HashMap<OsString, FakeProgram>,
+    invocations: RefCell<Vec<ProcessRequest>>,
+    output: RefCell<String>
+}
+
+impl FakeProcessSpawner {
+    pub fn new() -> FakeProcessSpawner {
+        return FakeProcessSpawner {
+            programs: HashMap::new(),
+            invocations: RefCell::new(Vec::new()),
+            output: RefCell::new(String::new())
+        };
+    }
+
+    pub fn add_program<TProgram>(&mut self, path: &str, program: TProgram)
+    where
+        TProgram: Fn(&[String], &str, &mut dyn Write) -> i32 + 'static
+    {
+        self.programs.insert(OsString::from(path), Rc::new(program));
+    }
+
+    /// Every request this spawner received, in order.
+    pub fn invocations(&self) -> Vec<ProcessRequest> {
+        return self.invocations.borrow().clone();
+    }
+
+    pub fn take_output(&self) -> String {
+        return std::mem::take(&mut self.output.borrow_mut());
+    }
+}
+
+impl ProcessSpawner for FakeProcessSpawner {
+    fn run(&self, request: &ProcessRequest, input: Option<&str>, capture_output: bool) -> Result<(i32, String), CommandError> {
+        self.invocations.borrow_mut().push(request.clone());
+
+        let Some(program) = self.programs.get(&request.program) else {
+            return Err(CommandError::CouldNotExecute {
+                reason: format!("No fake program registered for {:?}", request.program)
+            });
+        };
+
+        let started_at = Instant::now();
+        let mut stdout = Vec::<u8>::new();
+        let exit_code = program(&request.arguments, input.unwrap_or(""), &mut stdout);
+        request.record_usage(exit_code, started_at.elapsed(), ResourceUsage::new());
+        let stdout = String::from_utf8_lossy(&stdout).into_owned();
+
+        if capture_output {
+            return Ok((exit_code, stdout));
+        }
+        self.output.borrow_mut().push_str(&stdout);
+        return Ok((exit_code, String::new()));
+    }
+}
@@ -0,0 +1,37 @@
+//! In-memory stand-ins for the parts of the shell that touch the operating system.
+//!
+//! ```
+//! use std::rc::Rc;
+//! use jomsole::Shell::Shell;
+//! use jomsole::fake::FakePathResolver::FakePathResolver;
+//! use jomsole::fake::FakeProcessSpawner::FakeProcessSpawner;
+//!
+//! let mut path_resolver = FakePathResolver::new();
+//! path_resolver.add_command("greet", "/bin/greet");
+//! path_resolver.add_command("upper", "/bin/upper");
+//!
+//! let mut process_spawner = FakeProcessSpawner::new();
+//! process_spawner.add_program("/bin/greet", |arguments, _, output| {
+//!     writeln!(output, "hello {}", arguments.join(" ")).unwrap();
+//!     return 0;
+//! });
+//! process_spawner.add_program("/bin/upper", |_, input, output| {
+//!     write!(output, "{}", input.to_uppercase()).unwrap();
+//!     return 3;
+//! });
+//! let process_spawner = Rc::new(process_spawner);
+//!
+//! let shell = Shell::builder()
+//!     .path_resolver(Rc::new(path_resolver))
+//!     .process_spawner(process_spawner.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(shell.eval("greet world | upper"), 3);
+//! assert_eq!(process_spawner.take_output(), "HELLO WORLD\n");
+//! ```
+
+pub mod FakePathResolver;
+pub mod FakeProcessSpawner;
+
+pub mod FakeFileSystem;
\ No newline at end of file
@@ -0,0 +1,494 @@
+use pest::{Parser, error::InputLocation, iterators::Pair};
+use crate::ShellState::{ShellState, Variable};
+
+#[derive(Parser)]
+#[grammar = "lib/jsh/arithmetic.pest"]
+struct ArithmeticParser;
+
+#[derive(Debug, Clone)]
+pub struct ArithmeticError {
+	pub message: String,
+	pub position: usize
+}
+
+impl ArithmeticError {
+	fn new(message: &str, position: usize) -> ArithmeticError {
+		return ArithmeticError {
+			message: message.to_string(),
+			position: position
+		};
+	}
+
+	pub fn describe(&self, source: &str) -> String {
+		return format!("{} at column {} in \"{}\"", self.message, self.position + 1, source.trim());
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOperator {
+	Power,
+	Multiply,
+	Divide,
+	Remainder,
+	Add,
+	Subtract,
+	ShiftLeft,
+	ShiftRight,
+	LessEqual,
+	GreaterEqual,
+	Less,
+	Greater,
+	Equal,
+	NotEqual,
+	BitAnd,
+	BitXor,
+	BitOr,
+	LogicalAnd,
+	LogicalOr
+}
+
+impl BinaryOperator {
+	fn from_str(operator: &str) -> Option<BinaryOperator> {
+		return match operator {
+			"**" => Some(BinaryOperator::Power),
+			"*" => Some(BinaryOperator::Multiply),
+			"/" => Some(BinaryOperator::Divide),
+			"%" => Some(BinaryOperator::Remainder),
+			"+" => Some(BinaryOperator::Add),
+			"-" => Some(BinaryOperator::Subtract),
+			"<<" => Some(BinaryOperator::ShiftLeft),
+			">>" => Some(BinaryOperator::ShiftRight),
+			"<=" => Some(BinaryOperator::LessEqual),
+			">=" => Some(BinaryOperator::GreaterEqual),
+			"<" => Some(BinaryOperator::Less),
+			">" => Some(BinaryOperator::Greater),
+			"==" => Some(BinaryOperator::Equal),
+			"!=" => Some(BinaryOperator::NotEqual),
+			"&" => Some(BinaryOperator::BitAnd),
+			"^" => Some(BinaryOperator::BitXor),
+			"|" => Some(BinaryOperator::BitOr),
+			"&&" => Some(BinaryOperator::LogicalAnd),
+			"||" => Some(BinaryOperator::LogicalOr),
+			_ => None
+		};
+	}
+
+	fn precedence(&self) -> u8 {
+		return match self {
+			BinaryOperator::LogicalOr => 1,
+			BinaryOperator::LogicalAnd => 2,
+			BinaryOperator::BitOr => 3,
+			BinaryOperator::BitXor => 4,
+			BinaryOperator::BitAnd => 5,
+			BinaryOperator::Equal | BinaryOperator::NotEqual => 6,
+			BinaryOperator::LessEqual | BinaryOperator::GreaterEqual
+				| BinaryOperator::Less | BinaryOperator::Greater => 7,
+			BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => 8,
+			BinaryOperator::Add | BinaryOperator::Subtract => 9,
+			BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Remainder => 10,
+			BinaryOperator::Power => 11
+		};
+	}
+
+	fn is_right_associative(&self) -> bool {
+		return *self == BinaryOperator::Power;
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum UnaryOperator {
+	Negate,
+	Plus,
+	LogicalNot,
+	BitNot
+}
+
+#[derive(Debug, Clone)]
+pub enum ArithmeticExpression {
+	Number(i64),
+	Variable {
+		name: String,
+		position: usize
+	},
+	Unary {
+		operator: UnaryOperator,
+		operand: Box<ArithmeticExpression>,
+		position: usize
+	},
+	Increment {
+		name: String,
+		delta: i64,
+		prefix: bool,
+		position: usize
+	},
+	Binary {
+		operator: BinaryOperator,
+		left: Box<ArithmeticExpression>,
+		right: Box<ArithmeticExpression>,
+		position: usize
+	},
+	Conditional {
+		condition: Box<ArithmeticExpression>,
+		then_value: Box<ArithmeticExpression>,
+		else_value: Box<ArithmeticExpression>
+	},
+	Assignment {
+		name: String,
+		operator: Option<BinaryOperator>,
+		value: Box<ArithmeticExpression>,
+		position: usize
+	}
+}
+
+pub fn parse_arithmetic(source: &str) -> Result<ArithmeticExpression, ArithmeticError> {
+	let arithmetic = match ArithmeticParser::parse(Rule::Arithmetic, source) {
+		Ok(mut pairs) => pairs.next().unwrap(),
+		Err(err) => {
+			let position = match err.location {
+				InputLocation::Pos(pos) => pos,
+				InputLocation::Span((start, _)) => start
+			};
+			return Err(ArithmeticError::new("syntax error", position));
+		}
+	};
+
+	let expression = arithmetic.into_inner()
+		.find(|pair| pair.as_rule() == Rule::Expression)
+		.unwrap();
+	return build_expression(expression);
+}
+
+fn variable_name(variable: Pair<Rule>) -> String {
+	return variable.into_inner()
+		.find(|pair| pair.as_rule() == Rule::VariableName)
+		.map(|pair| pair.as_str().to_string())
+		.unwrap_or_default();
+}
+
+fn build_expression(expression: Pair<Rule>) -> Result<ArithmeticExpression, ArithmeticError> {
+	let inner = expression.into_inner().next().unwrap();
+	return match inner.as_rule() {
+		Rule::AssignmentExpression => build_assignment(inner),
+		_ => build_conditional(inner)
+	};
+}
+
+fn build_assignment(assignment: Pair<Rule>) -> Result<ArithmeticExpression, ArithmeticError> {
+	let mut inner = assignment.into_inner();
+	let name = variable_name(inner.next().unwrap());
+	let operator = inner.next().unwrap();
+	let position = operator.as_span().start();
+	let value = build_expression(inner.next().unwrap())?;
+
+	let operator_text = operator.as_str();
+	let binary_operator = if operator_text == "=" {
+		None
+	} else {
+		BinaryOperator::from_str(&operator_text[..operator_text.len() - 1])
+	};
+
+	return Ok(ArithmeticExpression::Assignment {
+		name: name,
+		operator: binary_operator,
+		value: Box::new(value),
+		position: position
+	});
+}
+
+fn build_conditional(conditional: Pair<Rule>) -> Result<ArithmeticExpression, ArithmeticError> {
+	let mut inner = conditional.into_inner();
+	let condition = build_binary(inner.next().unwrap())?;
+
+	let Some(then_value) = inner.next() else {
+		return Ok(condition);
+	};
+	let else_value = inner.next().unwrap();
+
+	return Ok(ArithmeticExpression::Conditional {
+		condition: Box::new(condition),
+		then_value: Box::new(build_expression(then_value)?),
+		else_value: Box::new(build_conditional(else_value)?)
+	});
+}
+
+fn build_binary(binary: Pair<Rule>) -> Result<ArithmeticExpression, ArithmeticError> {
+	let mut operands = Vec::<ArithmeticExpression>::new();
+	let mut operators = Vec::<(BinaryOperator, usize)>::new();
+
+	for part in binary.into_inner() {
+		match part.as_rule() {
+			Rule::BinaryOperator => operators.push((
+				BinaryOperator::from_str(part.as_str()).unwrap(),
+				part.as_span().start()
+			)),
+			_ => operands.push(build_unary(part)?)
+		}
+	}
+
+	let mut operands = operands.into_iter();
+	let mut operators = operators.into_iter().peekable();
+	let first = operands.next().unwrap();
+	return Ok(climb_precedence(first, &mut operands, &mut operators, 0));
+}
+
+fn climb_precedence(
+	mut left: ArithmeticExpression,
+	operands: &mut std::vec::IntoIter<ArithmeticExpression>,
+	operators: &mut std::iter::Peekable<std::vec::IntoIter<(BinaryOperator, usize)>>,
+	minimum_precedence: u8
+) -> ArithmeticExpression {
+	while let Some((operator, _)) = operators.peek() {
+		if operator.precedence() < minimum_precedence {
+			break;
+		}
+		let (operator, position) = operators.next().unwrap();
+		let mut right = operands.next().unwrap();
+
+		while let Some((next_operator, _)) = operators.peek() {
+			let binds_tighter = next_operator.precedence() > operator.precedence()
+				|| (next_operator.is_right_associative() && next_operator.precedence() == operator.precedence());
+			if !binds_tighter {
+				break;
+			}
+			let next_minimum = if next_operator.precedence() > operator.precedence() {
+				operator.precedence() + 1
+			} else {
+				operator.precedence()
+			};
+			right = climb_precedence(right, operands, operators, next_minimum);
+		}
+
+		left = ArithmeticExpression::Binary {
+			operator: operator,
+			left: Box::new(left),
+			right: Box::new(right),
+			position: position
+		};
+	}
+	return left;
+}
+
+fn build_unary(unary: Pair<Rule>) -> Result<ArithmeticExpression, ArithmeticError> {
+	let mut prefixes = Vec::<Pair<Rule>>::new();
+	let mut primary = None;
+	let mut postfix = None;
+
+	for part in unary.into_inner() {
+		match part.as_rule() {
+			Rule::PrefixOperator => prefixes.push(part),
+			Rule::PostfixOperator => postfix = Some(part),
+			_ => primary = Some(part)
+		}
+	}
+
+	let primary = primary.unwrap().into_inner().next().unwrap();
+	let primary_position = primary.as_span().start();
+	if let Some(operator) = postfix.as_ref().filter(|_| primary.as_rule() != Rule::Variable) {
+		return Err(ArithmeticError::new(
+			&format!("operand of {} must be a variable", operator.as_str()),
+			operator.as_span().start()
+		));
+	}
+	let mut expression = match primary.as_rule() {
+		Rule::Number => ArithmeticExpression::Number(parse_number(primary.as_str(), primary_position)?),
+		Rule::Variable => {
+			let name = variable_name(primary);
+			match &postfix {
+				Some(operator) => ArithmeticExpression::Increment {
+					name: name,
+					delta: if operator.as_str() == "++" { 1 } else { -1 },
+					prefix: false,
+					position: operator.as_span().start()
+				},
+				None => ArithmeticExpression::Variable { name: name, position: primary_position }
+			}
+		},
+		_ => build_expression(primary)?
+	};
+
+	for prefix in prefixes.into_iter().rev() {
+		let position = prefix.as_span().start();
+		expression = match (prefix.as_str(), expression) {
+			("++", ArithmeticExpression::Variable { name, .. }) => ArithmeticExpression::Increment {
+				name: name,
+				delta: 1,
+				prefix: true,
+				position: position
+			},
+			("--", ArithmeticExpression::Variable { name, .. }) => ArithmeticExpression::Increment {
+				name: name,
+				delta: -1,
+				prefix: true,
+				position: position
+			},
+			// In front of anything but a variable, `++` and `--` are two signs: `--5` is 5.
+			("++", operand) => double_unary(UnaryOperator::Plus, operand, position),
+			("--", operand) => double_unary(UnaryOperator::Negate, operand, position),
+			(operator, operand) => ArithmeticExpression::Unary {
+				operator: match operator {
+					"+" => UnaryOperator::Plus,
+					"-" => UnaryOperator::Negate,
+					"!" => UnaryOperator::LogicalNot,
+					_ => UnaryOperator::BitNot
+				},
+				operand: Box::new(operand),
+				position: position
+			}
+		};
+	}
+
+	return Ok(expression);
+}
+
+fn double_unary(operator: UnaryOperator, operand: ArithmeticExpression, position: usize) -> ArithmeticExpression {
+	let inner = ArithmeticExpression::Unary {
+		operator: operator,
+		operand: Box::new(operand),
+		position: position + 1
+	};
+	return ArithmeticExpression::Unary {
+		operator: operator,
+		operand: Box::new(inner),
+		position: position
+	};
+}
+
+fn parse_number(text: &str, position: usize) -> Result<i64, ArithmeticError> {
+	let parsed = match text.strip_prefix("0x") {
+		Some(hex) => i64::from_str_radix(hex, 16),
+		None => text.parse::<i64>()
+	};
+	return parsed.map_err(|_| ArithmeticError::new("number too large", position));
+}
+
+fn read_variable(state: &ShellState, name: &str, position: usize) -> Result<i64, ArithmeticError> {
+	let value = match state.get_variable(name) {
+		Some(Variable::Scalar(value)) => value.clone(),
+		Some(Variable::Array(elements)) => elements.first().cloned().unwrap_or_default(),
+		None => String::new()
+	};
+
+	let trimmed = value.trim();
+	if trimmed.is_empty() {
+		return Ok(0);
+	}
+	return match trimmed.parse::<i64>() {
+		Ok(number) => Ok(number),
+		Err(_) => Err(ArithmeticError::new(
+			&format!("value of {} is not an integer: {}", name, trimmed),
+			position
+		))
+	};
+}
+
+fn apply_binary(operator: BinaryOperator, left: i64, right: i64, position: usize) -> Result<i64, ArithmeticError> {
+	let overflow = || ArithmeticError::new("arithmetic overflow", position);
+	let result = match operator {
+		BinaryOperator::Add => left.checked_add(right).ok_or_else(overflow)?,
+		BinaryOperator::Subtract => left.checked_sub(right).ok_or_else(overflow)?,
+		BinaryOperator::Multiply => left.checked_mul(right).ok_or_else(overflow)?,
+		BinaryOperator::Divide | BinaryOperator::Remainder => {
+			if right == 0 {
+				return Err(ArithmeticError::new("division by zero", position));
+			}
+			if operator == BinaryOperator::Divide {
+				left.checked_div(right).ok_or_else(overflow)?
+			} else {
+				left.checked_rem(right).ok_or_else(overflow)?
+			}
+		},
+		BinaryOperator::Power => {
+			if right < 0 {
+				return Err(ArithmeticError::new("negative exponent", position));
+			}
+			let exponent = u32::try_from(right).map_err(|_| overflow())?;
+			left.checked_pow(exponent).ok_or_else(overflow)?
+		},
+		BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight => {
+			let shift = u32::try_from(right)
+				.ok()
+				.filter(|shift| *shift < 64)
+				.ok_or_else(|| ArithmeticError::new("invalid shift amount", position))?;
+			if operator == BinaryOperator::ShiftLeft {
+				left.checked_shl(shift).ok_or_else(overflow)?
+			} else {
+				left.checked_shr(shift).ok_or_else(overflow)?
+			}
+		},
+		BinaryOperator::LessEqual => (left <= right) as i64,
+		BinaryOperator::GreaterEqual => (left >= right) as i64,
+		BinaryOperator::Less => (left < right) as i64,
+		BinaryOperator::Greater => (left > right) as i64,
+		BinaryOperator::Equal => (left == right) as i64,
+		BinaryOperator::NotEqual => (left != right) as i64,
+		BinaryOperator::BitAnd => left & right,
+		BinaryOperator::BitXor => left ^ right,
+		BinaryOperator::BitOr => left | right,
+		BinaryOperator::LogicalAnd => (left != 0 && right != 0) as i64,
+		BinaryOperator::LogicalOr => (left != 0 || right != 0) as i64
+	};
+	return Ok(result);
+}
+
+impl ArithmeticExpression {
+	pub fn evaluate(&self, state: &mut ShellState) -> Result<i64, ArithmeticError> {
+		return match self {
+			ArithmeticExpression::Number(number) => Ok(*number),
+			ArithmeticExpression::Variable { name, position } => read_variable(state, name, *position),
+			ArithmeticExpression::Unary { operator, operand, position } => {
+				let value = operand.evaluate(state)?;
+				match operator {
+					UnaryOperator::Plus => Ok(value),
+					UnaryOperator::Negate => value.checked_neg()
+						.ok_or_else(|| ArithmeticError::new("arithmetic overflow", *position)),
+					UnaryOperator::LogicalNot => Ok((value == 0) as i64),
+					UnaryOperator::BitNot => Ok(!value)
+				}
+			},
+			ArithmeticExpression::Increment { name, delta, prefix, position } => {
+				let old_value = read_variable(state, name, *position)?;
+				let new_value = apply_binary(BinaryOperator::Add, old_value, *delta, *position)?;
+				state.set_variable(name, Variable::Scalar(new_value.to_string()));
+				Ok(if *prefix { new_value } else { old_value })
+			},
+			ArithmeticExpression::Binary { operator: BinaryOperator::LogicalAnd, left, right, .. } => {
+				if left.evaluate(state)? == 0 {
+					return Ok(0);
+				}
+				Ok((right.evaluate(state)? != 0) as i64)
+			},
+			ArithmeticExpression::Binary { operator: BinaryOperator::LogicalOr, left, right, .. } => {
+				if left.evaluate(state)? != 0 {
+					return Ok(1);
+				}
+				Ok((right.evaluate(state)? != 0) as i64)
+			},
+			ArithmeticExpression::Binary { operator, left, right, position } => {
+				let left = left.evaluate(state)?;
+				let right = right.evaluate(state)?;
+				apply_binary(*operator, left, right, *position)
+			},
+			ArithmeticExpression::Conditional { condition, then_value, else_value } => {
+				if condition.evaluate(state)? != 0 {
+					then_value.evaluate(state)
+				} else {
+					else_value.evaluate(state)
+				}
+			},
+			ArithmeticExpression::Assignment { name, operator, value, position } => {
+				let value = value.evaluate(state)?;
+				let new_value = match operator {
+					Some(operator) => apply_binary(*operator, read_variable(state, name, *position)?, value, *position)?,
+					None => value
+				};
+				state.set_variable(name, Variable::Scalar(new_value.to_string()));
+				Ok(new_value)
+			}
+		};
+	}
+}
+
+pub fn evaluate_arithmetic(source: &str, state: &mut ShellState) -> Result<i64, String> {
+	let expression = parse_arithmetic(source).map_err(|err| err.describe(source))?;
+	return expression.evaluate(state).map_err(|err| err.describe(source));
+}
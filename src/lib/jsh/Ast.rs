@@ -0,0 +1,175 @@
+use crate::Commands::ExecuteCommand::CommandScope;
+use crate::Word::VariableSubscript;
+use crate::jsh::Arithmetic::ArithmeticExpression;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Span {
+	pub start: usize,
+	pub end: usize
+}
+
+impl Span {
+	pub fn new(start: usize, end: usize) -> Span {
+		return Span {
+			start: start,
+			end: end
+		};
+	}
+
+	pub fn text<'a>(&self, source: &'a str) -> &'a str {
+		return &source[self.start..self.end];
+	}
+
+	pub fn line_column(&self, source: &str) -> (usize, usize) {
+		let before = &source[..self.start];
+		let line = before.matches('\n').count() + 1;
+		let column = match before.rfind('\n') {
+			Some(newline) => before[newline + 1..].chars().count() + 1,
+			None => before.chars().count() + 1
+		};
+		return (line, column);
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuoteStyle {
+	Unquoted,
+	Double
+}
+
+#[derive(Debug, Clone)]
+pub enum WordPart {
+	Text(String),
+	Variable {
+		name: String,
+		subscript: VariableSubscript
+	},
+	LastStatus,
+	/// `$0` to `$9` or `${N}`.
+	Positional(usize),
+	/// `$#`
+	ParameterCount,
+	/// `$@`
+	AllParameters,
+	Arithmetic {
+		expression: ArithmeticExpression,
+		source: String
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct WordNode {
+	pub quote_style: QuoteStyle,
+	pub parts: Vec<WordPart>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub enum RedirectionKind {
+	Input(WordNode),
+	Output(WordNode),
+	Append(WordNode),
+	HereString(WordNode),
+	HereDocument {
+		delimiter: String,
+		quoted_delimiter: bool,
+		strip_tabs: bool,
+		lines: Vec<String>,
+		body: Vec<WordPart>,
+		body_span: Span
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct RedirectionNode {
+	pub kind: RedirectionKind,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub struct SimpleCommandNode {
+	pub scope: CommandScope,
+	pub name: WordNode,
+	pub arguments: Vec<WordNode>,
+	pub redirections: Vec<RedirectionNode>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub struct AssignmentNode {
+	pub name: String,
+	pub value: Option<WordNode>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub enum CompoundKind {
+	Subshell(Vec<ListNode>),
+	Group(Vec<ListNode>)
+}
+
+#[derive(Debug, Clone)]
+pub struct CompoundCommandNode {
+	pub kind: CompoundKind,
+	pub redirections: Vec<RedirectionNode>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandNode {
+	Simple(SimpleCommandNode),
+	Assignments(Vec<AssignmentNode>, Span),
+	Compound(CompoundCommandNode)
+}
+
+impl CommandNode {
+	pub fn span(&self) -> Span {
+		return match self {
+			CommandNode::Simple(command) => command.span,
+			CommandNode::Assignments(_, span) => *span,
+			CommandNode::Compound(command) => command.span
+		};
+	}
+}
+
+#[derive(Debug, Clone)]
+pub struct PipelineNode {
+	pub timed: bool,
+	/// `time -p`: report only real, user and sys time in the POSIX format.
+	pub posix_time_format: bool,
+	pub commands: Vec<CommandNode>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AndOrOperator {
+	And,
+	Or
+}
+
+#[derive(Debug, Clone)]
+pub struct AndOrNode {
+	pub first: PipelineNode,
+	pub rest: Vec<(AndOrOperator, PipelineNode)>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub struct ListNode {
+	pub items: Vec<AndOrNode>,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub struct CommentNode {
+	pub text: String,
+	pub is_shebang: bool,
+	pub span: Span
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramNode {
+	pub lists: Vec<ListNode>,
+	pub comments: Vec<CommentNode>,
+	pub span: Span
+}
@@ -0,0 +1,261 @@
+use crate::Word::{Word, WordSegment};
+
+/// The most words one word may brace-expand to. Expanding a word past it fails.
+pub const MAX_BRACE_EXPANSION_WORDS: u64 = 100_000;
+
+/// A character that may take part in brace expansion, or an expansion such as `$name` that is
+/// carried along as is.
+#[derive(Debug, Clone)]
+pub enum BraceToken<TSegment = WordSegment> {
+	Char(char),
+	Segment(TSegment)
+}
+
+fn is_char<TSegment>(token: &BraceToken<TSegment>, expected: char) -> bool {
+	return matches!(token, BraceToken::Char(c) if *c == expected);
+}
+
+fn find_closing_brace<TSegment>(tokens: &[BraceToken<TSegment>], open: usize) -> Option<usize> {
+	let mut depth = 0;
+	for (i, token) in tokens.iter().enumerate().skip(open) {
+		if is_char(token, '{') {
+			depth += 1;
+		} else if is_char(token, '}') {
+			depth -= 1;
+			if depth == 0 {
+				return Some(i);
+			}
+		}
+	}
+	return None;
+}
+
+fn split_alternatives<TSegment: Clone>(body: &[BraceToken<TSegment>]) -> Option<Vec<Vec<BraceToken<TSegment>>>> {
+	let mut alternatives = Vec::<Vec<BraceToken<TSegment>>>::new();
+	let mut current = Vec::<BraceToken<TSegment>>::new();
+	let mut depth = 0;
+
+	for token in body {
+		if is_char(token, '{') {
+			depth += 1;
+		} else if is_char(token, '}') {
+			depth -= 1;
+		} else if depth == 0 && is_char(token, ',') {
+			alternatives.push(std::mem::take(&mut current));
+			continue;
+		}
+		current.push(token.clone());
+	}
+
+	if alternatives.is_empty() {
+		return None;
+	}
+	alternatives.push(current);
+	return Some(alternatives);
+}
+
+fn parse_sequence_bound(text: &str) -> Option<(i64, usize, bool)> {
+	let digits = text.strip_prefix('-').unwrap_or(text);
+	if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+	let value = text.parse::<i64>().ok()?;
+	let padded = digits.len() > 1 && digits.starts_with('0');
+	return Some((value, text.len(), padded));
+}
+
+// The bounds of a `{start..end[..step]}` sequence. `width` zero-pads numbers; letters are
+// kept as their character codes.
+struct Sequence {
+	start: i64,
+	end: i64,
+	step: i64,
+	width: usize,
+	letters: bool
+}
+
+impl Sequence {
+	fn parse<TSegment>(body: &[BraceToken<TSegment>]) -> Option<Sequence> {
+		let mut text = String::new();
+		for token in body {
+			match token {
+				BraceToken::Char(c) => text.push(*c),
+				BraceToken::Segment(_) => return None
+			}
+		}
+
+		let parts: Vec<&str> = text.split("..").collect();
+		if parts.len() != 2 && parts.len() != 3 {
+			return None;
+		}
+		let step = match parts.get(2) {
+			Some(step) => parse_sequence_bound(step)?.0,
+			None => 1
+		};
+		let step = if step == 0 { 1 } else { step.unsigned_abs().min(i64::MAX as u64) as i64 };
+
+		if let (Some(start), Some(end)) = (parse_sequence_bound(parts[0]), parse_sequence_bound(parts[1])) {
+			let (start, start_width, start_padded) = start;
+			let (end, end_width, end_padded) = end;
+			return Some(Sequence {
+				start: start,
+				end: end,
+				step: step,
+				width: if start_padded || end_padded { start_width.max(end_width) } else { 0 },
+				letters: false
+			});
+		}
+
+		let mut start = parts[0].chars();
+		let mut end = parts[1].chars();
+		return match (start.next(), start.next(), end.next(), end.next()) {
+			(Some(start), None, Some(end), None) if start.is_ascii_alphabetic() && end.is_ascii_alphabetic() => Some(Sequence {
+				start: start as i64,
+				end: end as i64,
+				step: step,
+				width: 0,
+				letters: true
+			}),
+			_ => None
+		};
+	}
+
+	fn len(&self) -> u64 {
+		return (self.start.abs_diff(self.end) / self.step as u64).saturating_add(1);
+	}
+
+	fn values(&self) -> Vec<String> {
+		let mut values = Vec::<String>::new();
+		let mut value = self.start;
+		for _ in 0..self.len() {
+			values.push(self.format(value));
+			value = if self.start <= self.end { value.wrapping_add(self.step) } else { value.wrapping_sub(self.step) };
+		}
+		return values;
+	}
+
+	fn format(&self, value: i64) -> String {
+		if self.letters {
+			return (value as u8 as char).to_string();
+		}
+		if value < 0 {
+			return format!("-{:0>width$}", value.unsigned_abs(), width = self.width.saturating_sub(1));
+		}
+		return format!("{:0>width$}", value, width = self.width);
+	}
+}
+
+enum Expansion<TSegment> {
+	Alternatives(Vec<Vec<BraceToken<TSegment>>>),
+	Sequence(Sequence)
+}
+
+// The first brace pair that expands, with its position. Braces that neither list alternatives nor
+// form a sequence are left as they are.
+fn find_expansion<TSegment: Clone>(tokens: &[BraceToken<TSegment>]) -> Option<(usize, usize, Expansion<TSegment>)> {
+	for open in 0..tokens.len() {
+		if !is_char(&tokens[open], '{') {
+			continue;
+		}
+		let Some(close) = find_closing_brace(tokens, open) else {
+			continue;
+		};
+
+		let body = &tokens[open + 1..close];
+		if let Some(alternatives) = split_alternatives(body) {
+			return Some((open, close, Expansion::Alternatives(alternatives)));
+		}
+		if let Some(sequence) = Sequence::parse(body) {
+			return Some((open, close, Expansion::Sequence(sequence)));
+		}
+	}
+	return None;
+}
+
+fn expand_tokens(tokens: Vec<BraceToken>) -> Vec<Vec<BraceToken>> {
+	let Some((open, close, expansion)) = find_expansion(&tokens) else {
+		return vec![tokens];
+	};
+	let alternatives = match expansion {
+		Expansion::Alternatives(alternatives) => alternatives,
+		Expansion::Sequence(sequence) => sequence.values().iter()
+			.map(|value| value.chars().map(BraceToken::Char).collect())
+			.collect()
+	};
+
+	let prefix = &tokens[..open];
+	let suffix = &tokens[close + 1..];
+	let mut expanded = Vec::<Vec<BraceToken>>::new();
+	for alternative in alternatives {
+		let mut rest = alternative;
+		rest.extend_from_slice(suffix);
+		for tail in expand_tokens(rest) {
+			let mut word = prefix.to_vec();
+			word.extend(tail);
+			expanded.push(word);
+		}
+	}
+	return expanded;
+}
+
+// How many words `tokens` expands to, counting no further than just past `limit`.
+fn count_words<TSegment: Clone>(tokens: &[BraceToken<TSegment>], limit: u64) -> u64 {
+	let Some((_, close, expansion)) = find_expansion(tokens) else {
+		return 1;
+	};
+	let suffix = &tokens[close + 1..];
+	return match expansion {
+		Expansion::Alternatives(alternatives) => {
+			let mut count = 0u64;
+			for alternative in alternatives {
+				let mut rest = alternative;
+				rest.extend_from_slice(suffix);
+				count = count.saturating_add(count_words(&rest, limit));
+				if count > limit {
+					break;
+				}
+			}
+			count
+		},
+		Expansion::Sequence(sequence) => {
+			if sequence.len() > limit {
+				return sequence.len();
+			}
+			sequence.len().saturating_mul(count_words(suffix, limit))
+		}
+	};
+}
+
+/// Checks that a word does not brace-expand to more than [`MAX_BRACE_EXPANSION_WORDS`] words.
+pub fn check_brace_expansion<TSegment: Clone>(tokens: &[BraceToken<TSegment>]) -> Result<(), String> {
+	if count_words(tokens, MAX_BRACE_EXPANSION_WORDS) > MAX_BRACE_EXPANSION_WORDS {
+		return Err(format!("brace expansion produces more than {} words", MAX_BRACE_EXPANSION_WORDS));
+	}
+	return Ok(());
+}
+
+fn tokens_to_word(tokens: Vec<BraceToken>) -> Word {
+	let mut segments = Vec::<WordSegment>::new();
+	let mut text = String::new();
+	for token in tokens {
+		match token {
+			BraceToken::Char(c) => text.push(c),
+			BraceToken::Segment(segment) => {
+				if !text.is_empty() {
+					segments.push(WordSegment::Literal(std::mem::take(&mut text)));
+				}
+				segments.push(segment);
+			}
+		}
+	}
+	if !text.is_empty() {
+		segments.push(WordSegment::Literal(text));
+	}
+	return Word::new(segments);
+}
+
+pub fn expand_braces(tokens: Vec<BraceToken>) -> Vec<Word> {
+	return expand_tokens(tokens).into_iter()
+		.map(tokens_to_word)
+		.collect();
+}
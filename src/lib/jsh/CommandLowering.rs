@@ -0,0 +1,319 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::Builtin::BuiltinRegistry;
+use crate::Builtins::NiceBuiltin::nice_word_count;
+use crate::Command::Command;
+use crate::Commands::AssignmentCommand::AssignmentCommand;
+use crate::Commands::BuiltinCommand::BuiltinCommand;
+use crate::Commands::ConditionalCommand::{ConditionalCommand, ConditionalOperator};
+use crate::Commands::ExecuteCommand::{CommandScope, ExecuteCommand};
+use crate::Commands::GroupCommand::GroupCommand;
+use crate::Commands::InputRedirectCommand::{InputRedirectCommand, InputSource};
+use crate::Commands::ListCommand::ListCommand;
+use crate::Commands::NiceCommand::NiceCommand;
+use crate::Commands::OutputRedirectCommand::OutputRedirectCommand;
+use crate::Commands::PipeCommand::PipeCommand;
+use crate::Commands::SourceCommand::SourceCommand;
+use crate::Commands::SubshellCommand::SubshellCommand;
+use crate::Commands::TimeCommand::TimeCommand;
+use crate::Commands::TimeoutCommand::{timeout_word_count, TimeoutCommand};
+use crate::PathResolver::PathResolver;
+use crate::ProcessSpawner::ProcessSpawner;
+use crate::ShellState::ShellState;
+use crate::Word::{Word, WordSegment};
+use crate::jsh::Ast::{
+	AndOrNode, AndOrOperator, AssignmentNode, CommandNode, CompoundCommandNode, CompoundKind, ListNode,
+	PipelineNode, QuoteStyle, RedirectionKind, RedirectionNode, SimpleCommandNode, WordNode, WordPart
+};
+use crate::jsh::BraceExpansion::{check_brace_expansion, expand_braces, BraceToken};
+use crate::jsh::JshFormatter::format_word;
+use crate::jsh::{lowered_builtin, LoweredBuiltin};
+
+pub struct CommandLowering {
+	path_resolver: Rc<dyn PathResolver>,
+	process_spawner: Rc<dyn ProcessSpawner>,
+	state: Rc<RefCell<ShellState>>,
+	builtins: Rc<BuiltinRegistry>
+}
+
+fn lower_word_part(part: &WordPart) -> WordSegment {
+	return match part {
+		WordPart::Text(text) => WordSegment::Literal(text.clone()),
+		WordPart::Variable { name, subscript } => WordSegment::Variable {
+			name: name.clone(),
+			subscript: subscript.clone()
+		},
+		WordPart::LastStatus => WordSegment::LastStatus,
+		WordPart::Positional(index) => WordSegment::Positional(*index),
+		WordPart::ParameterCount => WordSegment::ParameterCount,
+		WordPart::AllParameters => WordSegment::AllParameters,
+		WordPart::Arithmetic { expression, source } => WordSegment::Arithmetic {
+			expression: expression.clone(),
+			source: source.clone()
+		}
+	};
+}
+
+fn lower_word(word: &WordNode) -> Word {
+	let mut segments = Vec::<WordSegment>::new();
+	if word.quote_style == QuoteStyle::Double {
+		segments.push(WordSegment::Literal(String::new()));
+	}
+	segments.extend(word.parts.iter().map(lower_word_part));
+	return Word::new(segments);
+}
+
+fn literal_text(word: &WordNode) -> Option<String> {
+	let mut text = String::new();
+	for part in &word.parts {
+		match part {
+			WordPart::Text(literal) => text.push_str(literal),
+			_ => return None
+		}
+	}
+	return Some(text);
+}
+
+fn lower_brace_expanded_word(word: &WordNode) -> Vec<Word> {
+	if word.quote_style != QuoteStyle::Unquoted {
+		return vec![lower_word(word)];
+	}
+
+	let mut tokens = Vec::<BraceToken>::new();
+	for part in &word.parts {
+		match part {
+			WordPart::Text(text) => tokens.extend(text.chars().map(BraceToken::Char)),
+			part => tokens.push(BraceToken::Segment(lower_word_part(part)))
+		}
+	}
+	if let Err(reason) = check_brace_expansion(&tokens) {
+		return vec![Word::new(vec![WordSegment::Unexpandable(format!("{}: {}", format_word(word), reason))])];
+	}
+	return expand_braces(tokens);
+}
+
+impl CommandLowering {
+	pub fn new(
+		path_resolver: Rc<dyn PathResolver>,
+		process_spawner: Rc<dyn ProcessSpawner>,
+		state: Rc<RefCell<ShellState>>,
+		builtins: Rc<BuiltinRegistry>
+	) -> CommandLowering {
+		return CommandLowering {
+			path_resolver: path_resolver,
+			process_spawner: process_spawner,
+			state: state,
+			builtins: builtins
+		};
+	}
+
+	pub fn lower_lists(&self, lists: &[ListNode]) -> Box<dyn Command> {
+		let mut commands: Vec<Box<dyn Command>> = lists.iter()
+			.map(|list| self.lower_list(list))
+			.collect();
+
+		if commands.len() == 1 {
+			return commands.pop().unwrap();
+		}
+		return Box::new(ListCommand::new(commands, self.state.clone()));
+	}
+
+	pub fn lower_list(&self, list: &ListNode) -> Box<dyn Command> {
+		let mut commands: Vec<Box<dyn Command>> = list.items.iter()
+			.map(|and_or| self.lower_and_or(and_or))
+			.collect();
+
+		if commands.len() == 1 {
+			return commands.pop().unwrap();
+		}
+		return Box::new(ListCommand::new(commands, self.state.clone()));
+	}
+
+	fn lower_and_or(&self, and_or: &AndOrNode) -> Box<dyn Command> {
+		let mut composed = self.lower_pipeline(&and_or.first);
+		for (operator, pipeline) in &and_or.rest {
+			let operator = match operator {
+				AndOrOperator::And => ConditionalOperator::AND,
+				AndOrOperator::Or => ConditionalOperator::OR
+			};
+			let right_command = self.lower_pipeline(pipeline);
+			composed = Box::new(ConditionalCommand::new(composed, operator, right_command, self.state.clone()));
+		}
+		return composed;
+	}
+
+	pub fn lower_pipeline(&self, pipeline: &PipelineNode) -> Box<dyn Command> {
+		let stages = pipeline.commands.iter()
+			.map(|command| self.lower_command(command))
+			.collect();
+
+		let pipeline_command = Box::new(PipeCommand::new(stages, self.state.clone()));
+		if pipeline.timed {
+			return Box::new(TimeCommand::new(pipeline_command, pipeline.posix_time_format, self.state.clone()));
+		}
+		return pipeline_command;
+	}
+
+	pub fn lower_command(&self, command: &CommandNode) -> Box<dyn Command> {
+		return match command {
+			CommandNode::Simple(command) => self.lower_simple_command(command),
+			CommandNode::Assignments(assignments, _) => self.lower_assignments(assignments),
+			CommandNode::Compound(command) => self.lower_compound_command(command)
+		};
+	}
+
+	fn lower_compound_command(&self, command: &CompoundCommandNode) -> Box<dyn Command> {
+		let composed: Box<dyn Command> = match &command.kind {
+			CompoundKind::Subshell(lists) => Box::new(SubshellCommand::new(
+				self.lower_lists(lists),
+				self.state.clone()
+			)),
+			CompoundKind::Group(lists) => Box::new(GroupCommand::new(self.lower_lists(lists)))
+		};
+
+		return self.apply_redirections(composed, &command.redirections);
+	}
+
+	fn lower_assignments(&self, assignments: &[AssignmentNode]) -> Box<dyn Command> {
+		let assignments = assignments.iter()
+			.map(|assignment| {
+				let value = match &assignment.value {
+					Some(value) => lower_word(value),
+					None => Word::new(vec![WordSegment::Literal(String::new())])
+				};
+				(assignment.name.clone(), value)
+			})
+			.collect();
+
+		return Box::new(AssignmentCommand::new(assignments, self.state.clone()));
+	}
+
+	fn lower_timeout(&self, command: &SimpleCommandNode) -> Box<dyn Command> {
+		let literals: Vec<Option<String>> = command.arguments.iter().map(literal_text).collect();
+		let option_count = timeout_word_count(&literals);
+		let options = command.arguments[..option_count].iter()
+			.flat_map(lower_brace_expanded_word)
+			.collect();
+
+		let timed_command = self.lower_prefixed_command(command, option_count);
+
+		let composed = Box::new(TimeoutCommand::new(options, timed_command, self.state.clone()));
+		return self.apply_redirections(composed, &command.redirections);
+	}
+
+	fn lower_prefixed_command(&self, command: &SimpleCommandNode, option_count: usize) -> Option<Box<dyn Command>> {
+		let name = command.arguments.get(option_count)?;
+		return Some(self.lower_simple_command(&SimpleCommandNode {
+			scope: CommandScope::ANY,
+			name: name.clone(),
+			arguments: command.arguments[option_count + 1..].to_vec(),
+			redirections: Vec::new(),
+			span: command.span
+		}));
+	}
+
+	fn lower_nice(&self, command: &SimpleCommandNode) -> Option<Box<dyn Command>> {
+		let literals: Vec<Option<String>> = command.arguments.iter().map(literal_text).collect();
+		let option_count = nice_word_count(&literals);
+		let niced_command = self.lower_prefixed_command(command, option_count)?;
+		let options = command.arguments[..option_count].iter()
+			.flat_map(lower_brace_expanded_word)
+			.collect();
+
+		let composed = Box::new(NiceCommand::new(options, niced_command, self.state.clone()));
+		return Some(self.apply_redirections(composed, &command.redirections));
+	}
+
+	fn lower_source(&self, command: &SimpleCommandNode, include: bool) -> Box<dyn Command> {
+		let arguments = command.arguments.iter()
+			.flat_map(lower_brace_expanded_word)
+			.collect();
+
+		let source = SourceCommand::new(
+			arguments,
+			self.path_resolver.clone(),
+			self.process_spawner.clone(),
+			self.state.clone(),
+			self.builtins.clone()
+		);
+		let composed = Box::new(if include { source.include() } else { source });
+		return self.apply_redirections(composed, &command.redirections);
+	}
+
+	fn lower_simple_command(&self, command: &SimpleCommandNode) -> Box<dyn Command> {
+		if command.scope == CommandScope::ANY {
+			let name = literal_text(&command.name);
+			match name.as_deref().and_then(lowered_builtin) {
+				Some(LoweredBuiltin::Timeout) => return self.lower_timeout(command),
+				Some(LoweredBuiltin::Source) => return self.lower_source(command, false),
+				Some(LoweredBuiltin::Include) => return self.lower_source(command, true),
+				None => {}
+			}
+			if name.as_deref() == Some("nice") {
+				if let Some(composed) = self.lower_nice(command) {
+					return composed;
+				}
+			}
+		}
+
+		let mut arguments = lower_brace_expanded_word(&command.name);
+		let command_name = arguments.remove(0);
+		for argument in &command.arguments {
+			arguments.extend(lower_brace_expanded_word(argument));
+		}
+
+		let composed: Box<dyn Command> = match (&command.scope, command_name.as_literal()) {
+			(CommandScope::ANY, Some(name)) if self.builtins.get(&name).is_some() => Box::new(BuiltinCommand::new(
+				name.clone(),
+				self.builtins.get(&name).unwrap(),
+				arguments,
+				self.state.clone()
+			)),
+			_ => Box::new(ExecuteCommand::new(
+				command_name,
+				command.scope,
+				arguments,
+				self.path_resolver.clone(),
+				self.process_spawner.clone(),
+				self.state.clone()
+			))
+		};
+
+		return self.apply_redirections(composed, &command.redirections);
+	}
+
+	fn apply_redirections(&self, command: Box<dyn Command>, redirections: &[RedirectionNode]) -> Box<dyn Command> {
+		let mut composed = command;
+		for redirection in redirections.iter().rev() {
+			composed = match &redirection.kind {
+				RedirectionKind::Input(target) => Box::new(InputRedirectCommand::new(
+					composed,
+					InputSource::File(lower_word(target)),
+					self.state.clone()
+				)),
+				RedirectionKind::HereString(text) => {
+					let mut word = lower_word(text);
+					word.push(WordSegment::Literal("\n".to_string()));
+					Box::new(InputRedirectCommand::new(composed, InputSource::Text(word), self.state.clone()))
+				},
+				RedirectionKind::HereDocument { body, .. } => {
+					let mut segments = vec![WordSegment::Literal(String::new())];
+					segments.extend(body.iter().map(lower_word_part));
+					Box::new(InputRedirectCommand::new(
+						composed,
+						InputSource::Text(Word::new(segments)),
+						self.state.clone()
+					))
+				},
+				RedirectionKind::Output(target) => Box::new(
+					OutputRedirectCommand::new(composed, lower_word(target), false, self.state.clone())
+				),
+				RedirectionKind::Append(target) => Box::new(
+					OutputRedirectCommand::new(composed, lower_word(target), true, self.state.clone())
+				)
+			};
+		}
+		return composed;
+	}
+}
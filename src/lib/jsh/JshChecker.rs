@@ -0,0 +1,161 @@
+use crate::Builtin::BuiltinRegistry;
+use crate::Builtins::NiceBuiltin::nice_word_count;
+use crate::CommandParser::CommandParserError;
+use crate::Commands::ExecuteCommand::CommandScope;
+use crate::Commands::TimeoutCommand::timeout_word_count;
+use crate::PathResolver::PathResolver;
+use crate::jsh::Ast::{CommandNode, CompoundKind, ListNode, QuoteStyle, Span, WordNode, WordPart};
+use crate::jsh::JshSyntaxParser::script_chunks;
+use crate::jsh::{lowered_builtin, LoweredBuiltin};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+	Error,
+	Warning
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	pub message: String,
+	pub line: usize,
+	pub column: usize
+}
+
+impl Diagnostic {
+	fn new(severity: Severity, message: String, source: &str, position: usize) -> Diagnostic {
+		let (line, column) = Span::new(position, position).line_column(source);
+		return Diagnostic {
+			severity: severity,
+			message: message,
+			line: line,
+			column: column
+		};
+	}
+
+	pub fn describe(&self, file_name: &str) -> String {
+		let severity = match self.severity {
+			Severity::Error => "error",
+			Severity::Warning => "warning"
+		};
+		return format!("{}:{}:{}: {}: {}", file_name, self.line, self.column, severity, self.message);
+	}
+}
+
+fn literal_command_name(word: &WordNode) -> Option<String> {
+	let mut name = String::new();
+	for part in &word.parts {
+		match part {
+			WordPart::Text(text) => name.push_str(text),
+			_ => return None
+		}
+	}
+	if word.quote_style == QuoteStyle::Unquoted && name.contains('{') {
+		return None;
+	}
+	return Some(name);
+}
+
+struct CommandChecker<'a> {
+	path_resolver: &'a dyn PathResolver,
+	builtins: &'a BuiltinRegistry,
+	source: &'a str,
+	offset: usize,
+	diagnostics: Vec<Diagnostic>
+}
+
+impl<'a> CommandChecker<'a> {
+	fn check_lists(&mut self, lists: &[ListNode]) {
+		for list in lists {
+			for and_or in &list.items {
+				let pipelines = std::iter::once(&and_or.first)
+					.chain(and_or.rest.iter().map(|(_, pipeline)| pipeline));
+				for pipeline in pipelines {
+					for command in &pipeline.commands {
+						self.check_command(command);
+					}
+				}
+			}
+		}
+	}
+
+	fn check_command(&mut self, command: &CommandNode) {
+		match command {
+			CommandNode::Simple(command) => {
+				if command.scope != CommandScope::ANY {
+					return;
+				}
+				let mut name_word = &command.name;
+				let mut arguments = command.arguments.as_slice();
+				loop {
+					let literals: Vec<Option<String>> = arguments.iter().map(literal_command_name).collect();
+					let name = literal_command_name(name_word);
+					let option_count = match name.as_deref() {
+						Some(name) if lowered_builtin(name) == Some(LoweredBuiltin::Timeout) => timeout_word_count(&literals),
+						Some("nice") if nice_word_count(&literals) < literals.len() => nice_word_count(&literals),
+						_ => break
+					};
+					let Some(timed_command) = arguments.get(option_count) else {
+						return;
+					};
+					name_word = timed_command;
+					arguments = &arguments[option_count + 1..];
+				}
+				let Some(name) = literal_command_name(name_word) else {
+					return;
+				};
+				if self.builtins.get(&name).is_some() || lowered_builtin(&name).is_some() {
+					return;
+				}
+				if self.path_resolver.resolve_command_global(&name).is_err() {
+					self.diagnostics.push(Diagnostic::new(
+						Severity::Warning,
+						format!("command not found: {}", name),
+						self.source,
+						self.offset + name_word.span.start
+					));
+				}
+			},
+			CommandNode::Compound(command) => match &command.kind {
+				CompoundKind::Subshell(lists) | CompoundKind::Group(lists) => self.check_lists(lists)
+			},
+			CommandNode::Assignments(_, _) => {}
+		}
+	}
+}
+
+fn check_commands(
+	program_lists: &[ListNode],
+	source: &str,
+	offset: usize,
+	path_resolver: &dyn PathResolver,
+	builtins: &BuiltinRegistry
+) -> Vec<Diagnostic> {
+	let mut checker = CommandChecker {
+		path_resolver: path_resolver,
+		builtins: builtins,
+		source: source,
+		offset: offset,
+		diagnostics: Vec::new()
+	};
+	checker.check_lists(program_lists);
+	return checker.diagnostics;
+}
+
+/// Checks a script one top-level command at a time, the way it would run, so a syntax error is
+/// reported and checking carries on with the command after it.
+pub fn check_script(source: &str, path_resolver: &dyn PathResolver, builtins: &BuiltinRegistry) -> Vec<Diagnostic> {
+	let mut diagnostics = Vec::<Diagnostic>::new();
+	for chunk in script_chunks(source) {
+		match chunk.program {
+			Ok(program) => {
+				diagnostics.extend(check_commands(&program.lists, source, chunk.start, path_resolver, builtins));
+			},
+			Err(err) => {
+				let position = (chunk.start + err.position().unwrap_or(0)).min(source.len());
+				diagnostics.push(Diagnostic::new(Severity::Error, err.describe(), source, position));
+			}
+		}
+	}
+	return diagnostics;
+}
@@ -1,19 +1,39 @@
+use std::cell::RefCell;
 use std::rc::Rc;
-use pest::{Parser, iterators::Pairs, iterators::Pair};
+use log::{debug, trace};
 
-use crate::lib::{CommandParser::{CommandParser, CommandParserError}, Command::Command, Commands::ExecuteCommand::ExecuteCommand};
-use crate::lib::Commands::ExecuteCommand::CommandScope;
-use crate::lib::Commands::PipeCommand::PipeCommand;
-use crate::lib::PathResolver::PathResolver;
+use crate::{CommandParser::{CommandParser, CommandParserError}, Command::Command};
+use crate::Builtin::BuiltinRegistry;
+use crate::PathResolver::PathResolver;
+use crate::ProcessSpawner::ProcessSpawner;
+use crate::ShellState::ShellState;
+use crate::jsh::Ast::{CommentNode, ProgramNode, Span};
+use crate::jsh::CommandLowering::CommandLowering;
+use crate::jsh::JshSyntaxParser::{parse_command_line, parse_script};
 
 #[derive(Debug, Clone)]
 pub struct JshCommandParserError {
 	reason: String,
+	incomplete: bool,
+	position: Option<usize>
 }
 
 impl JshCommandParserError {
 	pub fn new(reason: String) -> JshCommandParserError {
-		return JshCommandParserError { reason:  reason };
+		return JshCommandParserError { reason:  reason, incomplete: false, position: None };
+	}
+
+	pub fn incomplete(reason: String) -> JshCommandParserError {
+		return JshCommandParserError { reason: reason, incomplete: true, position: None };
+	}
+
+	pub fn at(mut self, position: usize) -> JshCommandParserError {
+		self.position = Some(position);
+		return self;
+	}
+
+	pub fn position(&self) -> Option<usize> {
+		return self.position;
 	}
 }
 
@@ -21,167 +41,67 @@ impl CommandParserError for JshCommandParserError {
     fn describe(&self) -> String {
         return self.reason.clone();
     }
-}
-
-pub struct JshCommandParser {
-	path_resolver: Rc<dyn PathResolver>
-}
 
-macro_rules! get_next_or_err {
-	($it:expr, $expected_rule:expr, $err_msg:expr) => {
-		{
-		let Some(next) = $it.next() else
-			{return Err(JshCommandParserError::new($err_msg.to_string()))};
-
-		if (next.as_rule() != $expected_rule) {
-			return Err(JshCommandParserError::new($err_msg.to_string()));
-		}
-		next
-		}
-	}
+    fn is_incomplete(&self) -> bool {
+        return self.incomplete;
+    }
 }
 
-#[derive(Parser)]
-#[grammar = "lib/jsh.pest"]
-struct JshParser;
+/// A top-level list of a script with the span it was parsed from.
+pub type ScriptCommand = (Span, Box<dyn Command>);
 
-fn assert_rule_type(pair: &Pair<Rule>, rule: Rule, error: &str) -> Result<(), JshCommandParserError> {
-	if (pair.as_rule() != rule) {
-		return Err(JshCommandParserError::new(error.to_string()));
-	}
-	return Ok(());
+pub struct JshCommandParser {
+	lowering: CommandLowering,
+	state: Rc<RefCell<ShellState>>
 }
 
 impl JshCommandParser {
-	pub fn new(path_resolver: Rc<dyn PathResolver>) -> JshCommandParser {
+	pub fn new(
+		path_resolver: Rc<dyn PathResolver>,
+		process_spawner: Rc<dyn ProcessSpawner>,
+		state: Rc<RefCell<ShellState>>,
+		builtins: Rc<BuiltinRegistry>
+	) -> JshCommandParser {
 		return JshCommandParser {
-			path_resolver: path_resolver
+			lowering: CommandLowering::new(path_resolver, process_spawner, state.clone(), builtins),
+			state: state
 		};
 	}
 
-	pub fn compose_command_from_composition(&self, target: Pair<Rule>) -> Result<Box<dyn Command>, JshCommandParserError> {
-		assert_rule_type(&target, Rule::CommandComposition, "Expected composition")?;
-
-		return self.compose_command_from_command_composition(target);
+	pub fn parse_comments(&self, source: &str) -> Result<Vec<CommentNode>, JshCommandParserError> {
+		return Ok(parse_script(source)?.comments);
 	}
 
-	fn compose_command_from_command_composition(&self, command: Pair<Rule>) -> Result<Box<dyn Command>, JshCommandParserError> {
-		assert_rule_type(&command, Rule::CommandComposition, "Expected composition command")?;
-
-		let mut inner = command.into_inner();
-		let next = get_next_or_err!(inner, Rule::ExecuteCommand, "Expected execute command");
-
-		let first_command = self.compose_command_from_execute_command(next)?;
-
-		let optional_next = inner.next();
-		if (optional_next.is_none()) {
-			return Ok(first_command);
-		}
-
-		let next = optional_next.unwrap();
-
-		if next.as_rule() == Rule::SerialCommand {
-			let left_command = self.compose_serial_command(next)?;
-			return Ok(Box::new(PipeCommand::new(left_command, first_command)));
-		}
-
-		return Err(JshCommandParserError::new("Expected serial command".to_string()))
-	}
-
-	fn compose_serial_command(&self, command: Pair<Rule>) -> Result<Box<dyn Command>, JshCommandParserError> {
-		assert_rule_type(&command, Rule::SerialCommand, "Expected serial command")?;
-
-		let mut inner = command.into_inner();
-		let next = get_next_or_err!(inner, Rule::CommandComposition, "Expected command composition");
-		return self.compose_command_from_composition(next);
-	}
-
-	fn compose_command_from_execute_command(&self, command: Pair<Rule>) -> Result<Box<dyn Command>, JshCommandParserError> {
-		assert_rule_type(&command, Rule::ExecuteCommand, "Expected execute command")?;
-
-		let mut inner = command.into_inner();
-		let next = get_next_or_err!(inner, Rule::ScopedCommand, "Expected command part");
-
-		let (command_name, command_scope) = self.scoped_command(next)?;
-
-		let mut arguments = Vec::<String>::new();
-
-		for command in inner {
-			if (command.as_rule() != Rule::Argument) {
-				return Err(JshCommandParserError::new("Expected command part".to_string()));
-			}
-			arguments.push(self.parse_argument(command)?);
-		}
-
-		return Ok(Box::new(ExecuteCommand::new(command_name, command_scope, arguments, self.path_resolver.clone())));
-	}
-
-	fn parse_argument(&self, argument: Pair<Rule>) -> Result<String, JshCommandParserError> {
-		let mut inner = argument.into_inner();
-		let next = inner.next();
-		match next {
-			Some(argument) => {
-				match argument.as_rule() {
-					Rule::PlainArgument => Ok(argument.as_str().to_string()),
-					Rule::QuotedArgument => Ok(self.parse_quoted_argument(argument)?),
-					_ => Err(JshCommandParserError::new("Expected plain argument or quoted argument".to_string()))
-				}
-			},
-			None => Err(JshCommandParserError::new("Expected plain argument or quoted argument".to_string()))
-		}
-	}
-
-	fn parse_quoted_argument(&self, plain_argument: Pair<Rule>) -> Result<String, JshCommandParserError> {
-		if (plain_argument.as_rule() != Rule::QuotedArgument) {
-			return Err(JshCommandParserError::new("Expected quoted argument".to_string()));
-		}
-		let mut inner = plain_argument.into_inner();
-		let next = get_next_or_err!(inner, Rule::QuotedContent, "Expected quoted argument");
-
-		return Ok(next.as_str().to_string());
-	}
-
-	fn scoped_command(&self, scoped_command: Pair<Rule>) -> Result<(String, CommandScope), JshCommandParserError> {
-		assert_rule_type(&scoped_command, Rule::ScopedCommand, "Expected execute command")?;
-		let mut inner = scoped_command.into_inner();
-
-		let next = match inner.next() {
-			Some(command) => command,
-			None => { return Err(JshCommandParserError::new("Expected local scope command or any scope command".to_string()));}
-		};
-
-		return match next.as_rule() {
-			Rule::LocalScopeCommand => Ok((self.parse_argument(next)?, CommandScope::LOCAL)),
-			Rule::AnyScopeCommand => Ok((self.parse_any_scoped_command(next)?, CommandScope::ANY)),
-			_ => Err(JshCommandParserError::new("Expected local scope command or any scope command".to_string()))
-		};
-	}
-
-	fn parse_any_scoped_command(&self, command: Pair<Rule>) -> Result<String, JshCommandParserError> {
-		let mut inner = command.into_inner();
-		let next = get_next_or_err!(inner, Rule::Argument, "Expected argument");
-
-		return self.parse_argument(next);
+	/// Lowers each top-level list of a parsed script separately, keeping its span so that errors can
+	/// be reported with a line number.
+	pub fn lower_script_lists(&self, program: &ProgramNode) -> Vec<ScriptCommand> {
+		return program.lists.iter()
+			.map(|list| (list.span, self.lowering.lower_list(list)))
+			.collect();
 	}
 }
 
 impl CommandParser for JshCommandParser {
 	type TCommandParserError = JshCommandParserError;
 
-	fn parse_command(&self, command: &str) -> Result<Box<dyn Command>, JshCommandParserError> {
-		let parse_result = JshParser::parse(Rule::CommandComposition, command);
-		if (!parse_result.is_ok()) {
-			return Result::Err(JshCommandParserError::new("Syntax error".to_string()));
-		}
+	fn parse_command(&self, command: &str) -> Result<Option<Box<dyn Command>>, JshCommandParserError> {
+		debug!("Parsing {:?}", command);
+		let program = match parse_command_line(command) {
+			Ok(program) => program,
+			Err(err) => {
+				debug!("Parse failed: {}", err.describe());
+				return Err(err);
+			}
+		};
+		trace!("Parsed {:#?}", program.lists);
 
-		let first_pair = parse_result.unwrap().peek();
-		if (first_pair.is_none()) {
-			return Result::Err(JshCommandParserError::new("No command".to_string()));
+		if self.state.borrow().options.verbose {
+			eprintln!("{}", command);
 		}
 
-		return self.compose_command_from_composition(first_pair.unwrap());
+		if program.lists.is_empty() {
+			return Ok(None);
+		}
+		return Ok(Some(self.lowering.lower_lists(&program.lists)));
 	}
-
-
-	
-}
\ No newline at end of file
+}
@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+
+use crate::Commands::ExecuteCommand::CommandScope;
+use crate::Word::VariableSubscript;
+use crate::jsh::Ast::{
+	AndOrNode, AndOrOperator, AssignmentNode, CommandNode, CommentNode, CompoundCommandNode, CompoundKind,
+	ListNode, PipelineNode, QuoteStyle, RedirectionKind, RedirectionNode, SimpleCommandNode, Span, WordNode,
+	WordPart
+};
+use crate::jsh::JshCommandParser::JshCommandParserError;
+use crate::jsh::JshSyntaxParser::parse_script;
+use crate::jsh::KEYWORDS;
+
+const INDENT: &str = "\t";
+
+struct PendingHereDocument {
+	delimiter: String,
+	strip_tabs: bool,
+	lines: Vec<String>,
+	end: usize
+}
+
+struct JshFormatter<'a> {
+	source: &'a str,
+	comments: VecDeque<CommentNode>,
+	pending_here_documents: Vec<PendingHereDocument>,
+	output: String,
+	last_end: usize
+}
+
+fn is_safe_unquoted(text: &str) -> bool {
+	return !text.is_empty()
+		&& !text.starts_with("./")
+		&& text.chars().all(|c| c.is_ascii_alphanumeric() || "-_./:@%+^".contains(c));
+}
+
+fn starts_with_name_character(parts: &[WordPart]) -> bool {
+	return match parts.first() {
+		Some(WordPart::Text(text)) => text.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+		_ => false
+	};
+}
+
+fn format_word_parts(parts: &[WordPart]) -> String {
+	let mut text = String::new();
+	for (i, part) in parts.iter().enumerate() {
+		match part {
+			WordPart::Text(literal) => text.push_str(literal),
+			WordPart::Variable { name, subscript: VariableSubscript::None } => {
+				if starts_with_name_character(&parts[i + 1..]) {
+					text.push_str(&format!("${{{}}}", name));
+				} else {
+					text.push_str(&format!("${}", name));
+				}
+			},
+			WordPart::Variable { name, subscript: VariableSubscript::Index(index) } => {
+				text.push_str(&format!("${{{}[{}]}}", name, index));
+			},
+			WordPart::Variable { name, subscript: VariableSubscript::All } => {
+				text.push_str(&format!("${{{}[@]}}", name));
+			},
+			WordPart::LastStatus => text.push_str("$?"),
+			WordPart::Positional(index) if *index < 10 => text.push_str(&format!("${}", index)),
+			WordPart::Positional(index) => text.push_str(&format!("${{{}}}", index)),
+			WordPart::ParameterCount => text.push_str("$#"),
+			WordPart::AllParameters => text.push_str("$@"),
+			WordPart::Arithmetic { source, .. } => text.push_str(&format!("$(( {} ))", source.trim()))
+		}
+	}
+	return text;
+}
+
+pub(crate) fn format_word(word: &WordNode) -> String {
+	let text = format_word_parts(&word.parts);
+	if word.quote_style == QuoteStyle::Unquoted {
+		return text;
+	}
+
+	let only_text = word.parts.iter().all(|part| matches!(part, WordPart::Text(_)));
+	if only_text && is_safe_unquoted(&text) {
+		return text;
+	}
+	return format!("\"{}\"", text);
+}
+
+// Unquoting a reserved word in command position would turn the command into the keyword.
+fn format_command_name(word: &WordNode) -> String {
+	let text = format_word(word);
+	if word.quote_style != QuoteStyle::Unquoted && KEYWORDS.contains(&text.as_str()) {
+		return format!("\"{}\"", text);
+	}
+	return text;
+}
+
+impl<'a> JshFormatter<'a> {
+	fn new(source: &'a str, comments: Vec<CommentNode>) -> JshFormatter<'a> {
+		return JshFormatter {
+			source: source,
+			comments: comments.into(),
+			pending_here_documents: Vec::new(),
+			output: String::new(),
+			last_end: 0
+		};
+	}
+
+	fn write_indent(&mut self, indent: usize) {
+		for _ in 0..indent {
+			self.output.push_str(INDENT);
+		}
+	}
+
+	fn end_line(&mut self, indent: usize) {
+		self.output.push('\n');
+		for here_document in std::mem::take(&mut self.pending_here_documents) {
+			for line in &here_document.lines {
+				self.output.push_str(line);
+				self.output.push('\n');
+			}
+			if here_document.strip_tabs {
+				self.write_indent(indent);
+			}
+			self.output.push_str(&here_document.delimiter);
+			self.output.push('\n');
+			self.last_end = self.last_end.max(here_document.end);
+		}
+	}
+
+	fn separate(&mut self, start: usize, first: &mut bool) {
+		if !*first && self.last_end < start && self.source[self.last_end..start].matches('\n').count() >= 2 {
+			self.output.push('\n');
+		}
+		*first = false;
+	}
+
+	fn flush_comments_before(&mut self, position: usize, indent: usize, first: &mut bool) {
+		while let Some(comment) = self.comments.front() {
+			if comment.span.start >= position {
+				break;
+			}
+			let comment = self.comments.pop_front().unwrap();
+			self.separate(comment.span.start, first);
+			self.write_indent(indent);
+			self.output.push_str(&comment.text);
+			self.end_line(indent);
+			self.last_end = self.last_end.max(comment.span.end);
+		}
+	}
+
+	fn write_trailing_comment(&mut self, after: usize, block_end: usize) {
+		let Some(comment) = self.comments.front() else {
+			return;
+		};
+		if comment.span.start < after || comment.span.start >= block_end {
+			return;
+		}
+		if self.source[after..comment.span.start].contains('\n') {
+			return;
+		}
+
+		let comment = self.comments.pop_front().unwrap();
+		self.output.push(' ');
+		self.output.push_str(&comment.text);
+		self.last_end = self.last_end.max(comment.span.end);
+	}
+
+	fn write_lists(&mut self, lists: &[ListNode], indent: usize, block_end: usize) {
+		let mut first = true;
+		for list in lists {
+			self.flush_comments_before(list.span.start, indent, &mut first);
+			self.separate(list.span.start, &mut first);
+			self.write_indent(indent);
+			self.write_list(list, indent);
+			self.last_end = self.last_end.max(list.span.end);
+			self.write_trailing_comment(list.span.end, block_end);
+			self.end_line(indent);
+		}
+		self.flush_comments_before(block_end, indent, &mut first);
+	}
+
+	fn write_list(&mut self, list: &ListNode, indent: usize) {
+		for (i, and_or) in list.items.iter().enumerate() {
+			if i > 0 {
+				self.output.push_str("; ");
+			}
+			self.write_and_or(and_or, indent);
+		}
+	}
+
+	fn write_and_or(&mut self, and_or: &AndOrNode, indent: usize) {
+		self.write_pipeline(&and_or.first, indent);
+		for (operator, pipeline) in &and_or.rest {
+			self.output.push_str(match operator {
+				AndOrOperator::And => " && ",
+				AndOrOperator::Or => " || "
+			});
+			self.write_pipeline(pipeline, indent);
+		}
+	}
+
+	fn write_pipeline(&mut self, pipeline: &PipelineNode, indent: usize) {
+		if pipeline.timed {
+			self.output.push_str("time ");
+		}
+		if pipeline.posix_time_format {
+			self.output.push_str("-p ");
+		}
+		for (i, command) in pipeline.commands.iter().enumerate() {
+			if i > 0 {
+				self.output.push_str(" | ");
+			}
+			self.write_command(command, indent);
+		}
+	}
+
+	fn write_command(&mut self, command: &CommandNode, indent: usize) {
+		match command {
+			CommandNode::Simple(command) => self.write_simple_command(command),
+			CommandNode::Assignments(assignments, _) => self.write_assignments(assignments),
+			CommandNode::Compound(command) => self.write_compound_command(command, indent)
+		}
+	}
+
+	fn write_simple_command(&mut self, command: &SimpleCommandNode) {
+		if command.scope == CommandScope::LOCAL {
+			self.output.push_str("./");
+		}
+		self.output.push_str(&format_command_name(&command.name));
+		for argument in &command.arguments {
+			self.output.push(' ');
+			self.output.push_str(&format_word(argument));
+		}
+		self.write_redirections(&command.redirections);
+	}
+
+	fn write_assignments(&mut self, assignments: &[AssignmentNode]) {
+		for (i, assignment) in assignments.iter().enumerate() {
+			if i > 0 {
+				self.output.push(' ');
+			}
+			self.output.push_str(&assignment.name);
+			self.output.push('=');
+			if let Some(value) = &assignment.value {
+				self.output.push_str(&format_word(value));
+			}
+		}
+	}
+
+	fn write_compound_command(&mut self, command: &CompoundCommandNode, indent: usize) {
+		let (open, close, lists) = match &command.kind {
+			CompoundKind::Subshell(lists) => ("(", ")", lists),
+			CompoundKind::Group(lists) => ("{", "}", lists)
+		};
+		let Span { start, end } = command.span;
+		let body_end = start + self.source[start..end].rfind(close).unwrap_or(end - start);
+
+		if self.source[start..body_end].contains('\n') {
+			self.output.push_str(open);
+			self.write_trailing_comment(start + 1, body_end);
+			self.end_line(indent);
+			self.write_lists(lists, indent + 1, body_end);
+			self.write_indent(indent);
+			self.output.push_str(close);
+		} else {
+			self.output.push_str(open);
+			if close == "}" {
+				self.output.push(' ');
+			}
+			for (i, list) in lists.iter().enumerate() {
+				if i > 0 {
+					self.output.push_str("; ");
+				}
+				self.write_list(list, indent);
+			}
+			if close == "}" {
+				self.output.push_str("; ");
+			}
+			self.output.push_str(close);
+		}
+		self.write_redirections(&command.redirections);
+	}
+
+	fn write_redirections(&mut self, redirections: &[RedirectionNode]) {
+		for redirection in redirections {
+			self.output.push(' ');
+			match &redirection.kind {
+				RedirectionKind::Input(target) => self.output.push_str(&format!("< {}", format_word(target))),
+				RedirectionKind::Output(target) => self.output.push_str(&format!("> {}", format_word(target))),
+				RedirectionKind::Append(target) => self.output.push_str(&format!(">> {}", format_word(target))),
+				RedirectionKind::HereString(text) => self.output.push_str(&format!("<<< {}", format_word(text))),
+				RedirectionKind::HereDocument { delimiter, quoted_delimiter, strip_tabs, lines, body_span, .. } => {
+					self.output.push_str(if *strip_tabs { "<<-" } else { "<<" });
+					if *quoted_delimiter {
+						self.output.push_str(&format!("'{}'", delimiter));
+					} else {
+						self.output.push_str(delimiter);
+					}
+					self.pending_here_documents.push(PendingHereDocument {
+						delimiter: delimiter.clone(),
+						strip_tabs: *strip_tabs,
+						lines: lines.clone(),
+						end: body_span.end
+					});
+				}
+			}
+		}
+	}
+}
+
+pub fn format_script(source: &str) -> Result<String, JshCommandParserError> {
+	let program = parse_script(source)?;
+	let mut formatter = JshFormatter::new(source, program.comments);
+
+	if let Some(shebang) = formatter.comments.front().filter(|comment| comment.is_shebang).cloned() {
+		formatter.comments.pop_front();
+		formatter.output.push_str(&shebang.text);
+		formatter.output.push('\n');
+		formatter.last_end = shebang.span.end;
+	}
+
+	formatter.write_lists(&program.lists, 0, source.len());
+	return Ok(formatter.output);
+}
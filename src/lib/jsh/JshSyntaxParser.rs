@@ -0,0 +1,765 @@
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use pest::{Parser, error::InputLocation, iterators::Pair};
+
+use crate::CommandParser::CommandParserError;
+use crate::Commands::ExecuteCommand::CommandScope;
+use crate::Word::VariableSubscript;
+use crate::jsh::Arithmetic::parse_arithmetic;
+use crate::jsh::Ast::{
+	AndOrNode, AndOrOperator, AssignmentNode, CommandNode, CommentNode, CompoundCommandNode, CompoundKind,
+	ListNode, PipelineNode, ProgramNode, QuoteStyle, RedirectionKind, RedirectionNode, SimpleCommandNode,
+	Span, WordNode, WordPart
+};
+use crate::jsh::JshCommandParser::JshCommandParserError;
+
+macro_rules! get_next_or_err {
+	($it:expr, $expected_rule:expr, $err_msg:expr) => {
+		{
+		let Some(next) = $it.next() else
+			{return Err(JshCommandParserError::new($err_msg.to_string()))};
+
+		if (next.as_rule() != $expected_rule) {
+			return Err(JshCommandParserError::new($err_msg.to_string()));
+		}
+		next
+		}
+	}
+}
+
+#[derive(Parser)]
+#[grammar = "lib/jsh.pest"]
+struct JshParser;
+
+fn span_of(pair: &Pair<Rule>) -> Span {
+	return Span::new(pair.as_span().start(), pair.as_span().end());
+}
+
+fn is_layout(pair: &Pair<Rule>) -> bool {
+	return matches!(pair.as_rule(), Rule::Comment | Rule::LineEnd);
+}
+
+fn assert_rule_type(pair: &Pair<Rule>, rule: Rule, error: &str) -> Result<(), JshCommandParserError> {
+	if pair.as_rule() != rule {
+		return Err(JshCommandParserError::new(error.to_string()));
+	}
+	return Ok(());
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Opening {
+	Quote,
+	Parenthesis,
+	Group,
+	// Braces of brace expansion and `${name}` are tracked only to pair up closing braces.
+	Brace
+}
+
+// A here-document body cut out of the source before it is parsed.
+struct HereDocumentBody {
+	lines: Vec<String>,
+	span: Span
+}
+
+// The source with here-document bodies blanked out, so that the grammar only sees commands, and
+// the innermost construct still open at the end, for reporting where it starts.
+struct ScannedSource {
+	text: String,
+	here_documents: VecDeque<HereDocumentBody>,
+	unclosed_construct: Option<(usize, &'static str)>
+}
+
+struct PendingHereDocument {
+	start: usize,
+	delimiter: String,
+	strip_tabs: bool
+}
+
+fn is_delimiter_byte(byte: u8) -> bool {
+	return b" \t\r\n|\"<>;&()'".contains(&byte);
+}
+
+// Reads the delimiter of a here-document whose `<<` starts at `start`, returning it with where the
+// delimiter ends.
+fn read_here_document_delimiter(source: &str, start: usize) -> Option<(PendingHereDocument, usize)> {
+	let bytes = source.as_bytes();
+	let mut position = start + 2;
+	let strip_tabs = bytes.get(position) == Some(&b'-');
+	if strip_tabs {
+		position += 1;
+	}
+	while matches!(bytes.get(position), Some(b' ' | b'\t')) {
+		position += 1;
+	}
+
+	let quote = bytes.get(position).copied().filter(|byte| *byte == b'"' || *byte == b'\'');
+	if quote.is_some() {
+		position += 1;
+	}
+	let delimiter_start = position;
+	while position < bytes.len() && !is_delimiter_byte(bytes[position]) {
+		position += 1;
+	}
+	if position == delimiter_start {
+		return None;
+	}
+	let here_document = PendingHereDocument {
+		start: start,
+		delimiter: source[delimiter_start..position].to_string(),
+		strip_tabs: strip_tabs
+	};
+	if quote.is_some() {
+		position += 1;
+	}
+	return Some((here_document, position));
+}
+
+// Reads the bodies of `pending` here-documents, one after the other, from the line starting at
+// `line_start`. Returns where the next line of commands begins.
+fn read_here_document_bodies(
+	source: &str,
+	mut line_start: usize,
+	pending: &[PendingHereDocument],
+	bodies: &mut VecDeque<HereDocumentBody>
+) -> Result<usize, JshCommandParserError> {
+	for here_document in pending {
+		let body_start = line_start;
+		let mut lines = Vec::<String>::new();
+		loop {
+			if line_start >= source.len() {
+				return Err(JshCommandParserError::incomplete("Unterminated here-document".to_string()).at(here_document.start));
+			}
+			let line_end = source[line_start..].find('\n').map_or(source.len(), |newline| line_start + newline);
+			let line = source[line_start..line_end].trim_end_matches('\r');
+			line_start = (line_end + 1).min(source.len());
+			// Only `<<-` allows the terminator to be indented with tabs.
+			let terminator = if here_document.strip_tabs { line.trim_start_matches('\t') } else { line };
+			if terminator == here_document.delimiter {
+				break;
+			}
+			lines.push(line.to_string());
+		}
+		bodies.push_back(HereDocumentBody {
+			lines: lines,
+			span: Span::new(body_start, line_start)
+		});
+	}
+	return Ok(line_start);
+}
+
+fn find_closing_parenthesis(bytes: &[u8], open: usize) -> Option<usize> {
+	let mut depth = 0;
+	for (i, byte) in bytes.iter().enumerate().skip(open) {
+		match byte {
+			b'(' => depth += 1,
+			b')' => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(i);
+				}
+			},
+			_ => {}
+		}
+	}
+	return None;
+}
+
+fn scan_source(source: &str) -> Result<ScannedSource, JshCommandParserError> {
+	let bytes = source.as_bytes();
+	let mut text = bytes.to_vec();
+	let mut here_documents = VecDeque::<HereDocumentBody>::new();
+	let mut openings = Vec::<(usize, Opening)>::new();
+	let mut pending_here_documents = Vec::<PendingHereDocument>::new();
+	let mut position = 0;
+
+	while position < bytes.len() {
+		let byte = bytes[position];
+		if openings.last().is_some_and(|(_, opening)| *opening == Opening::Quote) {
+			if byte == b'"' {
+				openings.pop();
+			}
+			position += 1;
+			continue;
+		}
+
+		let at_word_start = position == 0 || b" \t\n;|&(".contains(&bytes[position - 1]);
+		match byte {
+			b'"' => openings.push((position, Opening::Quote)),
+			b'$' if source[position..].starts_with("$((") => {
+				// Arithmetic can contain `<<` and unbalanced-looking parentheses of its own.
+				if let Some(close) = find_closing_parenthesis(bytes, position + 1) {
+					position = close + 1;
+					continue;
+				}
+			},
+			b'(' => openings.push((position, Opening::Parenthesis)),
+			b')' if openings.last().is_some_and(|(_, opening)| *opening == Opening::Parenthesis) => {
+				openings.pop();
+			},
+			b'{' => {
+				let group = at_word_start && matches!(bytes.get(position + 1), None | Some(b' ' | b'\t' | b'\r' | b'\n'));
+				openings.push((position, if group { Opening::Group } else { Opening::Brace }));
+			},
+			b'}' if openings.last().is_some_and(|(_, opening)| matches!(opening, Opening::Group | Opening::Brace)) => {
+				openings.pop();
+			},
+			b'#' if at_word_start => {
+				position = source[position..].find('\n').map_or(bytes.len(), |newline| position + newline);
+				continue;
+			},
+			b'<' if source[position..].starts_with("<<<") => {
+				position += 3;
+				continue;
+			},
+			b'<' if source[position..].starts_with("<<") => {
+				if let Some((here_document, end)) = read_here_document_delimiter(source, position) {
+					pending_here_documents.push(here_document);
+					position = end;
+					continue;
+				}
+			},
+			b'\n' if !pending_here_documents.is_empty() => {
+				let next_line = read_here_document_bodies(source, position + 1, &pending_here_documents, &mut here_documents)?;
+				for byte in &mut text[position + 1..next_line] {
+					if *byte != b'\n' {
+						*byte = b' ';
+					}
+				}
+				pending_here_documents.clear();
+				position = next_line;
+				continue;
+			},
+			_ => {}
+		}
+		position += 1;
+	}
+
+	if let Some(here_document) = pending_here_documents.first() {
+		return Err(JshCommandParserError::incomplete("Unterminated here-document".to_string()).at(here_document.start));
+	}
+	let unclosed_construct = openings.iter().rev()
+		.find(|(_, opening)| *opening != Opening::Brace)
+		.map(|(start, opening)| (*start, match opening {
+			Opening::Quote => "Unterminated quoted string",
+			Opening::Parenthesis => "Unclosed `(`",
+			_ => "Unclosed `{`"
+		}));
+
+	return Ok(ScannedSource {
+		// Only whole lines were blanked, so the text is still valid UTF-8.
+		text: String::from_utf8(text).unwrap(),
+		here_documents: here_documents,
+		unclosed_construct: unclosed_construct
+	});
+}
+
+fn parse_source(rule: Rule, scanned: &ScannedSource) -> Result<Pair<'_, Rule>, JshCommandParserError> {
+	let source = scanned.text.as_str();
+	let parse_result = JshParser::parse(rule, source);
+	if let Err(err) = parse_result {
+		let error_position = match err.location {
+			InputLocation::Pos(pos) => pos,
+			InputLocation::Span((_, end)) => end
+		};
+		if error_position >= source.len() {
+			if let Some((start, reason)) = scanned.unclosed_construct {
+				return Err(JshCommandParserError::incomplete(reason.to_string()).at(start));
+			}
+			// Point at the last thing written rather than at trailing blank lines.
+			return Err(JshCommandParserError::incomplete("Unexpected end of input".to_string()).at(source.trim_end().len()));
+		}
+		if source.ends_with("\\") {
+			return Err(JshCommandParserError::incomplete("Line continuation at end of input".to_string()).at(source.len()));
+		}
+		let unexpected = match source[error_position..].chars().next() {
+			Some('\n') | Some('\r') => "end of line".to_string(),
+			Some(c) => format!("`{}`", c),
+			None => "end of input".to_string()
+		};
+		return Err(JshCommandParserError::new(format!("Syntax error: unexpected {}", unexpected)).at(error_position));
+	}
+
+	return match parse_result.unwrap().next() {
+		Some(pair) => Ok(pair),
+		None => Err(JshCommandParserError::new("No command".to_string()))
+	};
+}
+
+fn build_program(program: Pair<Rule>, mut here_documents: VecDeque<HereDocumentBody>) -> Result<ProgramNode, JshCommandParserError> {
+	let span = span_of(&program);
+
+	let comments = program.clone()
+		.into_inner()
+		.flatten()
+		.filter(|pair| pair.as_rule() == Rule::Comment || pair.as_rule() == Rule::Shebang)
+		.map(|pair| CommentNode {
+			text: pair.as_str().to_string(),
+			is_shebang: pair.as_rule() == Rule::Shebang,
+			span: span_of(&pair)
+		})
+		.collect();
+
+	let mut lists = Vec::<ListNode>::new();
+	for list in program.into_inner().filter(|pair| pair.as_rule() == Rule::List) {
+		lists.push(build_list(list, &mut here_documents)?);
+	}
+
+	return Ok(ProgramNode {
+		lists: lists,
+		comments: comments,
+		span: span
+	});
+}
+
+/// A run of whole lines of a script and what parsing it gave. Positions in the program and in the
+/// error are relative to `start`.
+pub struct ScriptChunk {
+	pub start: usize,
+	pub program: Result<ProgramNode, JshCommandParserError>
+}
+
+/// Reads a script one top-level command at a time, the way the line-based runner does: lines are
+/// added to a chunk until it parses or fails for a reason other than running out of input. A syntax
+/// error therefore only affects the chunk it is in.
+pub struct ScriptChunks<'a> {
+	source: &'a str,
+	offset: usize
+}
+
+pub fn script_chunks(source: &str) -> ScriptChunks<'_> {
+	return ScriptChunks {
+		source: source,
+		offset: 0
+	};
+}
+
+impl Iterator for ScriptChunks<'_> {
+	type Item = ScriptChunk;
+
+	fn next(&mut self) -> Option<ScriptChunk> {
+		if self.offset >= self.source.len() {
+			return None;
+		}
+		let start = self.offset;
+		let mut end = start;
+		loop {
+			end = self.source[end..].find('\n').map_or(self.source.len(), |newline| end + newline + 1);
+			let program = parse_script(&self.source[start..end]);
+			let incomplete = program.as_ref().is_err_and(|err| err.is_incomplete());
+			if !incomplete || end == self.source.len() {
+				self.offset = end;
+				return Some(ScriptChunk {
+					start: start,
+					program: program
+				});
+			}
+		}
+	}
+}
+
+fn parse_program(rule: Rule, source: &str) -> Result<ProgramNode, JshCommandParserError> {
+	let mut scanned = scan_source(source)?;
+	let here_documents = std::mem::take(&mut scanned.here_documents);
+	return build_program(parse_source(rule, &scanned)?, here_documents);
+}
+
+pub fn parse_command_line(source: &str) -> Result<ProgramNode, JshCommandParserError> {
+	return parse_program(Rule::CommandLine, source);
+}
+
+pub fn parse_script(source: &str) -> Result<ProgramNode, JshCommandParserError> {
+	return parse_program(Rule::Script, source);
+}
+
+fn build_list(list: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<ListNode, JshCommandParserError> {
+	assert_rule_type(&list, Rule::List, "Expected command list")?;
+	let span = span_of(&list);
+
+	let mut items = Vec::<AndOrNode>::new();
+	for and_or in list.into_inner().filter(|pair| !is_layout(pair)) {
+		items.push(build_and_or(and_or, here_documents)?);
+	}
+
+	return Ok(ListNode {
+		items: items,
+		span: span
+	});
+}
+
+fn build_compound_list(compound: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<Vec<ListNode>, JshCommandParserError> {
+	let mut lists = Vec::<ListNode>::new();
+	for list in compound.into_inner().filter(|pair| !is_layout(pair)) {
+		lists.push(build_list(list, here_documents)?);
+	}
+	return Ok(lists);
+}
+
+fn build_and_or(and_or: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<AndOrNode, JshCommandParserError> {
+	assert_rule_type(&and_or, Rule::AndOr, "Expected command")?;
+	let span = span_of(&and_or);
+
+	let mut inner = and_or.into_inner().filter(|pair| !is_layout(pair)).peekable();
+	let first = build_pipeline(&mut inner, here_documents, "Expected command")?;
+
+	let mut rest = Vec::<(AndOrOperator, PipelineNode)>::new();
+	while let Some(operator) = inner.next() {
+		let operator = match operator.as_str() {
+			"&&" => AndOrOperator::And,
+			"||" => AndOrOperator::Or,
+			_ => return Err(JshCommandParserError::new("Expected && or ||".to_string()))
+		};
+		rest.push((operator, build_pipeline(&mut inner, here_documents, "Expected command after && or ||")?));
+	}
+
+	return Ok(AndOrNode {
+		first: first,
+		rest: rest,
+		span: span
+	});
+}
+
+fn build_pipeline<'a>(
+	inner: &mut Peekable<impl Iterator<Item = Pair<'a, Rule>>>,
+	here_documents: &mut VecDeque<HereDocumentBody>,
+	error: &str
+) -> Result<PipelineNode, JshCommandParserError> {
+	let keyword = inner.next_if(|pair| pair.as_rule() == Rule::TimeKeyword);
+	let posix_option = inner.next_if(|pair| pair.as_rule() == Rule::TimePosixOption);
+	let composition = get_next_or_err!(inner, Rule::CommandComposition, error);
+	let mut span = span_of(&composition);
+	if let Some(keyword) = &keyword {
+		span.start = span_of(keyword).start;
+	}
+
+	let mut commands = Vec::<CommandNode>::new();
+	collect_pipeline_stages(composition, &mut commands, here_documents)?;
+
+	return Ok(PipelineNode {
+		timed: keyword.is_some(),
+		posix_time_format: posix_option.is_some(),
+		commands: commands,
+		span: span
+	});
+}
+
+fn collect_pipeline_stages(
+	command: Pair<Rule>,
+	stages: &mut Vec<CommandNode>,
+	here_documents: &mut VecDeque<HereDocumentBody>
+) -> Result<(), JshCommandParserError> {
+	assert_rule_type(&command, Rule::CommandComposition, "Expected composition command")?;
+
+	let mut inner = command.into_inner();
+	let Some(next) = inner.next() else {
+		return Err(JshCommandParserError::new("Expected command".to_string()));
+	};
+
+	stages.push(match next.as_rule() {
+		Rule::ExecuteCommand => CommandNode::Simple(build_simple_command(next, here_documents)?),
+		Rule::CompoundCommand => CommandNode::Compound(build_compound_command(next, here_documents)?),
+		Rule::AssignmentCommand => {
+			let span = span_of(&next);
+			CommandNode::Assignments(build_assignments(next)?, span)
+		},
+		_ => return Err(JshCommandParserError::new("Expected command".to_string()))
+	});
+
+	let Some(next) = inner.next() else {
+		return Ok(());
+	};
+
+	if next.as_rule() == Rule::SerialCommand {
+		let mut serial_inner = next.into_inner()
+			.filter(|pair| !is_layout(pair));
+		let composition = get_next_or_err!(serial_inner, Rule::CommandComposition, "Expected command composition");
+		return collect_pipeline_stages(composition, stages, here_documents);
+	}
+
+	return Err(JshCommandParserError::new("Expected serial command".to_string()))
+}
+
+fn build_compound_command(command: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<CompoundCommandNode, JshCommandParserError> {
+	assert_rule_type(&command, Rule::CompoundCommand, "Expected compound command")?;
+	let span = span_of(&command);
+
+	let mut inner = command.into_inner();
+	let Some(body) = inner.next() else {
+		return Err(JshCommandParserError::new("Expected subshell or command group".to_string()));
+	};
+
+	let kind = match body.as_rule() {
+		Rule::Subshell => CompoundKind::Subshell(build_compound_list(body, here_documents)?),
+		Rule::CommandGroup => CompoundKind::Group(build_compound_list(body, here_documents)?),
+		_ => return Err(JshCommandParserError::new("Expected subshell or command group".to_string()))
+	};
+
+	let mut redirections = Vec::<RedirectionNode>::new();
+	for redirection in inner {
+		redirections.push(build_redirection(redirection, here_documents)?);
+	}
+
+	return Ok(CompoundCommandNode {
+		kind: kind,
+		redirections: redirections,
+		span: span
+	});
+}
+
+fn build_assignments(command: Pair<Rule>) -> Result<Vec<AssignmentNode>, JshCommandParserError> {
+	assert_rule_type(&command, Rule::AssignmentCommand, "Expected assignment")?;
+
+	let mut assignments = Vec::<AssignmentNode>::new();
+	for assignment in command.into_inner() {
+		let span = span_of(&assignment);
+		let mut inner = assignment.into_inner();
+		let name = get_next_or_err!(inner, Rule::VariableName, "Expected variable name");
+		let value = match inner.next() {
+			Some(argument) => Some(build_word(argument)?),
+			None => None
+		};
+		assignments.push(AssignmentNode {
+			name: name.as_str().to_string(),
+			value: value,
+			span: span
+		});
+	}
+
+	return Ok(assignments);
+}
+
+fn build_simple_command(command: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<SimpleCommandNode, JshCommandParserError> {
+	assert_rule_type(&command, Rule::ExecuteCommand, "Expected execute command")?;
+	let span = span_of(&command);
+
+	let mut inner = command.into_inner();
+	let next = get_next_or_err!(inner, Rule::ScopedCommand, "Expected command part");
+	let (name, scope) = build_scoped_command(next)?;
+
+	let mut arguments = Vec::<WordNode>::new();
+	let mut redirections = Vec::<RedirectionNode>::new();
+
+	for command in inner {
+		match command.as_rule() {
+			Rule::Argument => arguments.push(build_word(command)?),
+			Rule::Redirection => redirections.push(build_redirection(command, here_documents)?),
+			_ => return Err(JshCommandParserError::new("Expected command part".to_string()))
+		}
+	}
+
+	return Ok(SimpleCommandNode {
+		scope: scope,
+		name: name,
+		arguments: arguments,
+		redirections: redirections,
+		span: span
+	});
+}
+
+fn build_scoped_command(scoped_command: Pair<Rule>) -> Result<(WordNode, CommandScope), JshCommandParserError> {
+	assert_rule_type(&scoped_command, Rule::ScopedCommand, "Expected execute command")?;
+	let mut inner = scoped_command.into_inner();
+
+	let Some(next) = inner.next() else {
+		return Err(JshCommandParserError::new("Expected local, direct or any scope command".to_string()));
+	};
+
+	return match next.as_rule() {
+		Rule::LocalScopeCommand => {
+			let mut command_inner = next.into_inner();
+			let argument = get_next_or_err!(command_inner, Rule::Argument, "Expected argument");
+			Ok((build_word(argument)?, CommandScope::LOCAL))
+		},
+		Rule::DirectScopeCommand => {
+			let mut command_inner = next.into_inner();
+			let argument = get_next_or_err!(command_inner, Rule::Argument, "Expected argument");
+			Ok((build_word(argument)?, CommandScope::DIRECT))
+		},
+		Rule::AnyScopeCommand => {
+			let mut command_inner = next.into_inner();
+			let argument = get_next_or_err!(command_inner, Rule::Argument, "Expected argument");
+			Ok((build_word(argument)?, CommandScope::ANY))
+		},
+		_ => Err(JshCommandParserError::new("Expected local, direct or any scope command".to_string()))
+	};
+}
+
+fn build_redirection(redirection: Pair<Rule>, here_documents: &mut VecDeque<HereDocumentBody>) -> Result<RedirectionNode, JshCommandParserError> {
+	assert_rule_type(&redirection, Rule::Redirection, "Expected redirection")?;
+	let span = span_of(&redirection);
+
+	let mut inner = redirection.into_inner();
+	let Some(next) = inner.next() else {
+		return Err(JshCommandParserError::new("Expected redirection".to_string()));
+	};
+
+	let kind = match next.as_rule() {
+		Rule::HereDocument => {
+			let Some(body) = here_documents.pop_front() else {
+				return Err(JshCommandParserError::new("Expected here-document body".to_string()).at(span.start));
+			};
+			build_here_document(next, body)?
+		},
+		rule => {
+			let mut redirection_inner = next.into_inner();
+			let argument = get_next_or_err!(redirection_inner, Rule::Argument, "Expected file name");
+			let target = build_word(argument)?;
+			match rule {
+				Rule::HereString => RedirectionKind::HereString(target),
+				Rule::InputRedirection => RedirectionKind::Input(target),
+				Rule::OutputRedirection => RedirectionKind::Output(target),
+				Rule::AppendRedirection => RedirectionKind::Append(target),
+				_ => return Err(JshCommandParserError::new("Expected redirection".to_string()))
+			}
+		}
+	};
+
+	return Ok(RedirectionNode {
+		kind: kind,
+		span: span
+	});
+}
+
+fn build_here_document(here_document: Pair<Rule>, body: HereDocumentBody) -> Result<RedirectionKind, JshCommandParserError> {
+	let mut strip_tabs = false;
+	let mut quoted_delimiter = false;
+	let mut delimiter = String::new();
+	for part in here_document.into_inner().flatten() {
+		match part.as_rule() {
+			Rule::HereDocStrip => strip_tabs = true,
+			Rule::QuotedHereDocDelimiter => quoted_delimiter = true,
+			Rule::HereDocDelimiter => delimiter = part.as_str().to_string(),
+			_ => {}
+		}
+	}
+
+	let body_span = body.span;
+	let lines = body.lines;
+
+	let mut text = String::new();
+	for line in &lines {
+		if strip_tabs {
+			text.push_str(line.trim_start_matches('\t'));
+		} else {
+			text.push_str(line);
+		}
+		text.push('\n');
+	}
+
+	let body = if quoted_delimiter {
+		vec![WordPart::Text(text)]
+	} else {
+		let expandable = match JshParser::parse(Rule::HereDocExpandable, &text) {
+			Ok(mut pairs) => pairs.next().unwrap(),
+			Err(_) => return Err(JshCommandParserError::new("Invalid here-document".to_string()))
+		};
+		let mut parts = Vec::<WordPart>::new();
+		for part in expandable.into_inner() {
+			match part.as_rule() {
+				Rule::HereDocText => parts.push(WordPart::Text(part.as_str().to_string())),
+				Rule::VariableReference => parts.push(build_variable_reference(part)?),
+				Rule::ArithmeticExpansion => parts.push(build_arithmetic_expansion(part)?),
+				_ => {}
+			}
+		}
+		parts
+	};
+
+	return Ok(RedirectionKind::HereDocument {
+		delimiter: delimiter,
+		quoted_delimiter: quoted_delimiter,
+		strip_tabs: strip_tabs,
+		lines: lines,
+		body: body,
+		body_span: body_span
+	});
+}
+
+fn build_word(argument: Pair<Rule>) -> Result<WordNode, JshCommandParserError> {
+	let span = span_of(&argument);
+	let Some(next) = argument.into_inner().next() else {
+		return Err(JshCommandParserError::new("Expected plain argument or quoted argument".to_string()));
+	};
+
+	return match next.as_rule() {
+		Rule::PlainArgument => {
+			Ok(WordNode {
+				quote_style: QuoteStyle::Unquoted,
+				parts: build_word_parts(next)?,
+				span: span
+			})
+		},
+		Rule::QuotedArgument => {
+			let mut inner = next.into_inner();
+			let content = get_next_or_err!(inner, Rule::QuotedContent, "Expected quoted argument");
+			Ok(WordNode {
+				quote_style: QuoteStyle::Double,
+				parts: build_word_parts(content)?,
+				span: span
+			})
+		},
+		_ => Err(JshCommandParserError::new("Expected plain argument or quoted argument".to_string()))
+	};
+}
+
+fn build_word_parts(word: Pair<Rule>) -> Result<Vec<WordPart>, JshCommandParserError> {
+	let mut parts = Vec::<WordPart>::new();
+	for part in word.into_inner() {
+		match part.as_rule() {
+			Rule::PlainText | Rule::QuotedText => parts.push(WordPart::Text(part.as_str().to_string())),
+			Rule::VariableReference => parts.push(build_variable_reference(part)?),
+			Rule::ArithmeticExpansion => parts.push(build_arithmetic_expansion(part)?),
+			_ => return Err(JshCommandParserError::new("Expected text or variable reference".to_string()))
+		}
+	}
+	return Ok(parts);
+}
+
+fn build_arithmetic_expansion(expansion: Pair<Rule>) -> Result<WordPart, JshCommandParserError> {
+	let mut inner = expansion.into_inner();
+	let text = get_next_or_err!(inner, Rule::ArithmeticText, "Expected arithmetic expression");
+
+	return match parse_arithmetic(text.as_str()) {
+		Ok(expression) => Ok(WordPart::Arithmetic {
+			expression: expression,
+			source: text.as_str().to_string()
+		}),
+		Err(err) => Err(JshCommandParserError::new(
+			format!("Arithmetic {}", err.describe(text.as_str()))
+		).at(text.as_span().start() + err.position))
+	};
+}
+
+fn build_variable_reference(reference: Pair<Rule>) -> Result<WordPart, JshCommandParserError> {
+	let mut inner = reference.into_inner();
+	let Some(next) = inner.next() else {
+		return Err(JshCommandParserError::new("Expected variable name".to_string()));
+	};
+
+	match next.as_rule() {
+		Rule::SpecialVariable => return Ok(match next.as_str() {
+			"#" => WordPart::ParameterCount,
+			"@" => WordPart::AllParameters,
+			_ => WordPart::LastStatus
+		}),
+		Rule::PositionalParameter | Rule::ShortPositionalParameter => return match next.as_str().parse::<usize>() {
+			Ok(index) => Ok(WordPart::Positional(index)),
+			Err(_) => Err(JshCommandParserError::new("Invalid positional parameter".to_string()))
+		},
+		_ => {}
+	}
+	assert_rule_type(&next, Rule::VariableName, "Expected variable name")?;
+
+	let subscript = match inner.next() {
+		None => VariableSubscript::None,
+		Some(subscript) if subscript.as_str() == "@" => VariableSubscript::All,
+		Some(subscript) => match subscript.as_str().parse::<usize>() {
+			Ok(index) => VariableSubscript::Index(index),
+			Err(_) => return Err(JshCommandParserError::new("Invalid array subscript".to_string()))
+		}
+	};
+
+	return Ok(WordPart::Variable {
+		name: next.as_str().to_string(),
+		subscript: subscript
+	});
+}
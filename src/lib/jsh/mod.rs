@@ -1 +1,33 @@
 pub mod JshCommandParser;
+pub mod Arithmetic;
+pub mod BraceExpansion;
+pub mod Ast;
+pub mod JshSyntaxParser;
+pub mod CommandLowering;
+pub mod JshFormatter;
+pub mod JshChecker;
+
+/// Reserved words the grammar recognizes in command position.
+pub const KEYWORDS: [&str; 1] = ["time"];
+
+/// Builtins that are lowered into dedicated commands instead of going through the builtin registry,
+/// because they need the parser or the shared state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoweredBuiltin {
+	Timeout,
+	Source,
+	Include
+}
+
+pub const LOWERED_BUILTINS: [(&str, LoweredBuiltin); 4] = [
+	("timeout", LoweredBuiltin::Timeout),
+	("source", LoweredBuiltin::Source),
+	(".", LoweredBuiltin::Source),
+	("include", LoweredBuiltin::Include)
+];
+
+pub fn lowered_builtin(name: &str) -> Option<LoweredBuiltin> {
+	return LOWERED_BUILTINS.iter()
+		.find(|(lowered_name, _)| *lowered_name == name)
+		.map(|(_, builtin)| *builtin);
+}
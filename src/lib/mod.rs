@@ -1,9 +1,37 @@
+//! Jomsole is a shell for the JomsaSH language.
+//!
+//! The [`Shell`](crate::Shell::Shell) type is the main entry point when embedding the shell. The
+//! lower level modules expose the parser ([`jsh`]), the executable command tree ([`Command`],
+//! [`Commands`]) and the extension points ([`CommandParser`], [`CommandInterface`],
+//! [`PathResolver`], [`ProcessSpawner`], [`Builtin`]). The [`fake`] module has in-memory
+//! implementations for testing without touching the operating system.
+
+#[macro_use]
+extern crate pest_derive;
+
 pub mod Jomsole;
 pub mod CommandParser;
 pub mod Command;
 pub mod CommandInterface;
-mod Commands;
+pub mod Commands;
 pub mod jsh;
-mod PathResolver;
+pub mod PathResolver;
+pub mod CachingPathResolver;
+pub mod SearchPathResolver;
+pub mod ProcessSpawner;
 pub mod nt;
-pub mod SimpleLogger;
\ No newline at end of file
+#[cfg(unix)]
+pub mod unix;
+pub mod fake;
+pub mod SimpleLogger;
+pub mod ShellState;
+pub mod Word;
+pub mod Builtin;
+pub mod Builtins;
+pub mod LineDiff;
+pub mod Trace;
+pub mod Deadline;
+pub mod ResourceUsage;
+pub mod ProcessAttributes;
+pub mod Shell;
+pub mod StartupFiles;
\ No newline at end of file
@@ -0,0 +1,104 @@
+use std::io;
+use crate::ProcessAttributes::{ProcessAttributes, ResourceLimit};
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type ResourceId = libc::__rlimit_resource_t;
+
+#[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+type ResourceId = libc::c_int;
+
+fn resource_id(resource: char) -> Option<ResourceId> {
+    return match resource {
+        'c' => Some(libc::RLIMIT_CORE),
+        'd' => Some(libc::RLIMIT_DATA),
+        'f' => Some(libc::RLIMIT_FSIZE),
+        'l' => Some(libc::RLIMIT_MEMLOCK),
+        'm' => Some(libc::RLIMIT_RSS),
+        'n' => Some(libc::RLIMIT_NOFILE),
+        's' => Some(libc::RLIMIT_STACK),
+        't' => Some(libc::RLIMIT_CPU),
+        'u' => Some(libc::RLIMIT_NPROC),
+        'v' => Some(libc::RLIMIT_AS),
+        _ => None
+    };
+}
+
+fn from_rlim(value: libc::rlim_t) -> Option<u64> {
+    if value == libc::RLIM_INFINITY {
+        return None;
+    }
+    return Some(value);
+}
+
+fn to_rlim(value: Option<u64>) -> libc::rlim_t {
+    return match value {
+        Some(value) => value as libc::rlim_t,
+        None => libc::RLIM_INFINITY
+    };
+}
+
+pub fn current_limit(resource: char) -> Option<ResourceLimit> {
+    let id = resource_id(resource)?;
+    let mut limit = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    if unsafe { libc::getrlimit(id, &mut limit) } != 0 {
+        return None;
+    }
+    return Some(ResourceLimit {
+        resource: resource,
+        soft: from_rlim(limit.rlim_cur),
+        hard: from_rlim(limit.rlim_max)
+    });
+}
+
+pub fn current_umask() -> Option<u32> {
+    // umask can only be read by setting it, so put the old value straight back.
+    let mask = unsafe { libc::umask(0o022) };
+    unsafe { libc::umask(mask) };
+    return Some(mask as u32);
+}
+
+/// Runs `create` with the process umask set to `mask`, for files the shell creates itself.
+pub fn with_umask<T>(mask: Option<u32>, create: impl FnOnce() -> T) -> T {
+    let Some(mask) = mask else {
+        return create();
+    };
+    let previous = unsafe { libc::umask(mask as libc::mode_t) };
+    let result = create();
+    unsafe { libc::umask(previous) };
+    return result;
+}
+
+pub fn current_niceness() -> Option<i32> {
+    return Some(unsafe { libc::getpriority(libc::PRIO_PROCESS, 0) });
+}
+
+pub fn is_privileged() -> bool {
+    return unsafe { libc::geteuid() } == 0;
+}
+
+// Runs in the forked child right before exec, so it must not allocate.
+pub fn apply_process_attributes(attributes: &ProcessAttributes) -> io::Result<()> {
+    for limit in attributes.limits() {
+        let Some(id) = resource_id(limit.resource) else {
+            continue;
+        };
+        let rlimit = libc::rlimit {
+            rlim_cur: to_rlim(limit.soft),
+            rlim_max: to_rlim(limit.hard)
+        };
+        if unsafe { libc::setrlimit(id, &rlimit) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    if let Some(mask) = attributes.umask {
+        unsafe { libc::umask(mask as libc::mode_t) };
+    }
+
+    if let Some(niceness) = attributes.niceness {
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, niceness) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    return Ok(());
+}
@@ -0,0 +1,18 @@
+use std::env;
+use std::ffi::{OsStr, OsString};
+use crate::SearchPathResolver::{FileSystemProbe, SearchPathResolver, SystemFileSystem};
+
+/// Unix lookup rules: `:` separated `PATH`, exact names only, case-sensitive.
+pub type UnixPathResolver = SearchPathResolver;
+
+pub const SEARCH_PATH_SEPARATOR: char = ':';
+
+pub fn new() -> UnixPathResolver {
+    let path = env::var_os("PATH").unwrap_or_default();
+    return with_probe(&path, Box::new(SystemFileSystem::new()));
+}
+
+/// Configures the Unix rules against any filesystem, from the value of `PATH`.
+pub fn with_probe(path: &OsStr, probe: Box<dyn FileSystemProbe>) -> UnixPathResolver {
+    return SearchPathResolver::new(path, SEARCH_PATH_SEPARATOR, vec![OsString::new()], true, probe);
+}
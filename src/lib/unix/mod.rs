@@ -0,0 +1,2 @@
+pub mod Resources;
+pub mod UnixPathResolver;
\ No newline at end of file
@@ -1,32 +1,285 @@
-mod lib;
+use std::env;
+use std::fs::{self, File};
+use std::io::{stdin, BufRead, BufReader, Cursor, IsTerminal, Read};
+use std::path::PathBuf;
+use std::process::exit;
+use log::debug;
 
-use std::rc::Rc;
-use log::{debug, info, LevelFilter};
-use lib::CommandInterface::Ecma48CommandInterface;
+use jomsole::Builtin::BuiltinRegistry;
+use jomsole::CommandInterface::{Ecma48CommandInterface, ScriptCommandInterface};
+use jomsole::CommandParser::CommandParserError;
+use jomsole::LineDiff::unified_diff;
+use jomsole::PathResolver::PathResolver;
+use jomsole::Shell::{Shell, ShellBuilder};
+use jomsole::ShellState::ShellOptions;
+use jomsole::SimpleLogger::{LogTarget, SimpleLogger};
+use jomsole::StartupFiles::{RcFile, StartupFiles};
+use jomsole::jsh::JshChecker::{check_script, Severity};
+use jomsole::jsh::JshFormatter::format_script;
 
-use crate::lib::{Jomsole::Jomsole, jsh::JshCommandParser::JshCommandParser};
-use crate::lib::nt::WindowsPathResolver;
-use crate::lib::SimpleLogger::SimpleLogger;
+fn run_formatter(arguments: &[String]) -> i32 {
+    let check = arguments.iter().any(|argument| argument == "--check");
+    let paths: Vec<&String> = arguments.iter().filter(|argument| *argument != "--check").collect();
 
-static LOGGER: SimpleLogger = SimpleLogger;
+    if paths.is_empty() {
+        let mut source = String::new();
+        if let Err(err) = stdin().read_to_string(&mut source) {
+            eprintln!("Could not read stdin: {}", err);
+            return 2;
+        }
+        return match format_script(&source) {
+            Ok(formatted) if check => {
+                if formatted == source {
+                    0
+                } else {
+                    print!("{}", unified_diff(&source, &formatted, "<stdin>", "<stdin> (formatted)"));
+                    1
+                }
+            },
+            Ok(formatted) => {
+                print!("{}", formatted);
+                0
+            },
+            Err(err) => {
+                eprintln!("<stdin>: {}", err.describe());
+                2
+            }
+        };
+    }
 
-#[macro_use]
-extern crate pest_derive;
+    let mut exit_code = 0;
+    for path in paths {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Could not read {}: {}", path, err);
+                exit_code = 2;
+                continue;
+            }
+        };
+
+        let formatted = match format_script(&source) {
+            Ok(formatted) => formatted,
+            Err(err) => {
+                eprintln!("{}: {}", path, err.describe());
+                exit_code = 2;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            let diff = unified_diff(&source, &formatted, path, &format!("{} (formatted)", path));
+            if diff.is_empty() {
+                println!("{}: line endings differ", path);
+            } else {
+                print!("{}", diff);
+            }
+            exit_code = exit_code.max(1);
+        } else if let Err(err) = fs::write(path, formatted) {
+            eprintln!("Could not write {}: {}", path, err);
+            exit_code = 2;
+        }
+    }
+    return exit_code;
+}
+
+fn run_syntax_check(paths: &[String], path_resolver: &dyn PathResolver, builtins: &BuiltinRegistry) -> i32 {
+    if paths.is_empty() {
+        eprintln!("--check: expected at least one script");
+        return 2;
+    }
+
+    let mut exit_code = 0;
+    for path in paths {
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                eprintln!("Could not read {}: {}", path, err);
+                exit_code = 2;
+                continue;
+            }
+        };
+
+        for diagnostic in check_script(&source, path_resolver, builtins) {
+            println!("{}", diagnostic.describe(path));
+            if diagnostic.severity == Severity::Error {
+                exit_code = exit_code.max(1);
+            }
+        }
+    }
+    return exit_code;
+}
+
+fn apply_shebang_flags(reader: &mut BufReader<File>, options: &mut ShellOptions) {
+    let Ok(buffer) = reader.fill_buf() else {
+        return;
+    };
+    let Some(shebang) = buffer.strip_prefix(b"#!") else {
+        return;
+    };
+
+    let line_end = shebang.iter().position(|byte| *byte == b'\n').unwrap_or(shebang.len());
+    let line = String::from_utf8_lossy(&shebang[..line_end]).to_string();
+    for flag in line.split_whitespace().skip(1) {
+        options.apply_flag(flag);
+    }
+}
+
+fn take_option_value(arguments: &mut Vec<String>, name: &str) -> Option<String> {
+    let first = arguments.first()?;
+    if let Some(value) = first.strip_prefix(&format!("{}=", name)) {
+        let value = value.to_string();
+        arguments.remove(0);
+        return Some(value);
+    }
+    if first != name {
+        return None;
+    }
+
+    arguments.remove(0);
+    if arguments.is_empty() {
+        eprintln!("{}: option requires an argument", name);
+        exit(2);
+    }
+    return Some(arguments.remove(0));
+}
+
+fn configure_logging(arguments: &mut Vec<String>) {
+    let mut log_spec = env::var("JOMSOLE_LOG").ok();
+    let mut log_file = env::var("JOMSOLE_LOG_FILE").ok();
+    loop {
+        if let Some(spec) = take_option_value(arguments, "--log-level") {
+            log_spec = Some(spec);
+        } else if let Some(path) = take_option_value(arguments, "--log-file") {
+            log_file = Some(path);
+        } else {
+            break;
+        }
+    }
+
+    let target = match log_file {
+        Some(path) => match fs::OpenOptions::new().create(true).append(true).open(&path) {
+            Ok(file) => LogTarget::File(file),
+            Err(err) => {
+                eprintln!("Could not open log file {}: {}", path, err);
+                exit(2);
+            }
+        },
+        None => LogTarget::Stderr
+    };
+
+    let logger = match SimpleLogger::new(log_spec.as_deref().unwrap_or("off"), target) {
+        Ok(logger) => logger,
+        Err(err) => {
+            eprintln!("--log-level: {}", err);
+            exit(2);
+        }
+    };
+    if let Err(err) = logger.install() {
+        eprintln!("Could not install logger: {}", err);
+    }
+}
+
+fn build_shell(builder: ShellBuilder) -> Shell {
+    return match builder.build() {
+        Ok(shell) => shell,
+        Err(err) => {
+            eprintln!("Could not start shell: {}", err.describe());
+            exit(2);
+        }
+    };
+}
 
 fn main() {
-    log::set_logger(&LOGGER)
-        .map(|()| log::set_max_level(LevelFilter::Off));
+    let mut arguments: Vec<String> = env::args().skip(1).collect();
+    configure_logging(&mut arguments);
+    debug!("Starting with arguments {:?}", arguments);
+
+    // A login shell is started with a leading `-` in its name, or with `--login`.
+    let mut login = env::args().next().is_some_and(|name| name.starts_with('-'));
+    let mut rc_file = RcFile::Default;
+    let mut options = ShellOptions::new();
+    let mut check_syntax = false;
+    let mut first_operand = 0;
+    while let Some(flag) = arguments.get(first_operand) {
+        match flag.as_str() {
+            "--login" | "-l" => login = true,
+            "--norc" => rc_file = RcFile::Disabled,
+            "--check" | "-n" => check_syntax = true,
+            "--rcfile" => {
+                let Some(path) = arguments.get(first_operand + 1) else {
+                    eprintln!("--rcfile: option requires an argument");
+                    exit(2);
+                };
+                rc_file = RcFile::Custom(PathBuf::from(path));
+                first_operand += 1;
+            },
+            flag if flag.starts_with("--rcfile=") => rc_file = RcFile::Custom(PathBuf::from(&flag["--rcfile=".len()..])),
+            flag if options.apply_flag(flag) => {},
+            _ => break
+        }
+        first_operand += 1;
+    }
+    let operands = &arguments[first_operand..];
+
+    // `fmt` and `--check` replace running the shell; like any other option they may follow the rest.
+    if operands.first().is_some_and(|operand| operand == "fmt") {
+        exit(run_formatter(&operands[1..]));
+    }
+    if check_syntax {
+        let shell = build_shell(Shell::builder());
+        exit(run_syntax_check(operands, shell.path_resolver().as_ref(), &shell.builtins()));
+    }
+
+    let interactive = operands.is_empty() && stdin().is_terminal();
+    let builder = match operands.first().map(|argument| argument.as_str()) {
+        Some("-c") => {
+            let Some(command) = operands.get(1) else {
+                eprintln!("-c: option requires an argument");
+                exit(2);
+            };
+
+            Shell::builder()
+                .command_interface(ScriptCommandInterface::new(Cursor::new(command.clone())))
+                .interactive(false)
+        },
+        Some(script_path) => {
+            let script = match File::open(script_path) {
+                Ok(file) => file,
+                Err(err) => {
+                    eprintln!("Could not open {}: {}", script_path, err);
+                    exit(127);
+                }
+            };
+
+            let mut reader = BufReader::new(script);
+            apply_shebang_flags(&mut reader, &mut options);
 
-    let path_resolver = if cfg!(windows) {
-        Rc::new(WindowsPathResolver::new())
-    } else {
-        panic!("Unsupported OS");
+            Shell::builder()
+                .command_interface(ScriptCommandInterface::new(reader))
+                .interactive(false)
+        },
+        None if !interactive => Shell::builder()
+            .command_interface(ScriptCommandInterface::new(stdin().lock()))
+            .interactive(false),
+        None => Shell::builder()
+            .command_interface(Ecma48CommandInterface::new())
+            .interactive(true)
     };
 
-    let jomsole = Jomsole::new(
-        JshCommandParser::new(path_resolver),
-        Ecma48CommandInterface::new(),
-        WindowsPathResolver::new()
-    );
-    jomsole.run();
+    let shell = build_shell(builder.options(options));
+    // `jomsole script args...` and `jomsole -c command name args...` set $0 and the positional parameters.
+    let parameters = if operands.first().is_some_and(|operand| operand == "-c") { operands.get(2..) } else { Some(operands) };
+    if let Some((script_name, parameters)) = parameters.and_then(|parameters| parameters.split_first()) {
+        let state = shell.state();
+        let mut state = state.borrow_mut();
+        state.set_script_name(script_name);
+        state.set_positional_parameters(parameters.to_vec());
+    }
+    shell.run_startup_files(&StartupFiles::new(login, interactive).rc_file(rc_file));
+    exit(shell.run());
 }
@@ -0,0 +1,76 @@
+use jomsole::ShellState::{ShellState, Variable};
+use jomsole::jsh::Arithmetic::{evaluate_arithmetic, parse_arithmetic, ArithmeticError};
+
+fn evaluate(source: &str) -> i64 {
+    return evaluate_arithmetic(source, &mut ShellState::new()).unwrap();
+}
+
+fn error(source: &str) -> ArithmeticError {
+    return parse_arithmetic(source).unwrap().evaluate(&mut ShellState::new()).unwrap_err();
+}
+
+fn scalar(state: &ShellState, name: &str) -> Option<String> {
+    return match state.get_variable(name) {
+        Some(Variable::Scalar(value)) => Some(value.clone()),
+        _ => None
+    };
+}
+
+#[test]
+fn operators_bind_by_precedence() {
+    assert_eq!(evaluate("1 + 2 * 3"), 7);
+    assert_eq!(evaluate("(1 + 2) * 3"), 9);
+    assert_eq!(evaluate("10 - 4 - 3"), 3);
+    assert_eq!(evaluate("1 + 2 < 4 && 3 == 3"), 1);
+    assert_eq!(evaluate("1 << 2 + 1"), 8);
+    assert_eq!(evaluate("6 & 3 | 8"), 10);
+    assert_eq!(evaluate("-2 * -3"), 6);
+    assert_eq!(evaluate("0 ? 1 : 2 ? 3 : 4"), 3);
+}
+
+#[test]
+fn power_is_right_associative() {
+    assert_eq!(evaluate("2 ** 3 ** 2"), 512);
+    assert_eq!(evaluate("2 * 3 ** 2"), 18);
+}
+
+#[test]
+fn doubled_signs_in_front_of_values_are_two_unary_operators() {
+    assert_eq!(evaluate("--5"), 5);
+    assert_eq!(evaluate("++5"), 5);
+    assert_eq!(evaluate("-(--5)"), -5);
+    assert_eq!(evaluate("--(2 + 3)"), 5);
+}
+
+#[test]
+fn assignment_operators_update_the_variable() {
+    let mut state = ShellState::new();
+    state.set_variable("i", Variable::Scalar("5".to_string()));
+
+    assert_eq!(evaluate_arithmetic("i += 3", &mut state), Ok(8));
+    assert_eq!(evaluate_arithmetic("i *= 2", &mut state), Ok(16));
+    assert_eq!(evaluate_arithmetic("i -= 1", &mut state), Ok(15));
+    assert_eq!(evaluate_arithmetic("i %= 4", &mut state), Ok(3));
+    assert_eq!(evaluate_arithmetic("j = i <<= 2", &mut state), Ok(12));
+    assert_eq!(scalar(&state, "i").as_deref(), Some("12"));
+    assert_eq!(scalar(&state, "j").as_deref(), Some("12"));
+    assert_eq!(evaluate_arithmetic("i++ + ++i", &mut state), Ok(26));
+    assert_eq!(scalar(&state, "i").as_deref(), Some("14"));
+}
+
+#[test]
+fn division_by_zero_and_overflow_are_reported_at_the_operator() {
+    let division = error("1 + 4 / 0");
+    assert_eq!(division.message, "division by zero");
+    assert_eq!(division.position, 6);
+    assert_eq!(error("7 % (1 - 1)").position, 2);
+
+    let overflow = error("9223372036854775807 + 1");
+    assert_eq!(overflow.message, "arithmetic overflow");
+    assert_eq!(overflow.position, 20);
+    assert_eq!(error("2 ** 64").position, 2);
+    assert_eq!(
+        evaluate_arithmetic("x = 1 / 0", &mut ShellState::new()),
+        Err("division by zero at column 7 in \"x = 1 / 0\"".to_string())
+    );
+}
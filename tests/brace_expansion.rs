@@ -0,0 +1,46 @@
+mod common;
+
+use common::{run_jomsole, shell_with_programs, text};
+
+#[test]
+fn ranges_count_in_either_direction() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("echo {1..5} {3..1} {-2..2} {c..a}"), 0);
+    assert_eq!(process_spawner.take_output(), "1 2 3 4 5 3 2 1 -2 -1 0 1 2 c b a\n");
+}
+
+#[test]
+fn ranges_keep_the_padding_of_their_bounds() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("echo {08..10} {007..9} {-05..5..5}"), 0);
+    assert_eq!(process_spawner.take_output(), "08 09 10 007 008 009 -05 000 005\n");
+}
+
+#[test]
+fn ranges_step_by_the_size_of_the_increment() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("echo {1..10..3} {1..10..-4} {a..e..2}"), 0);
+    assert_eq!(process_spawner.take_output(), "1 4 7 10 1 5 9 a c e\n");
+}
+
+#[test]
+fn alternatives_nest_and_keep_their_prefix_and_suffix() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("echo x{a,b{1,2}}y {a} \"{a,b}\""), 0);
+    assert_eq!(process_spawner.take_output(), "xay xb1y xb2y {a} {a,b}\n");
+}
+
+#[test]
+fn oversized_brace_expansions_fail_to_expand_and_name_the_word() {
+    let output = run_jomsole(&["-c", "echo a{1..1000}{1..1000}; echo $?"], "");
+    assert_eq!(text(&output.stdout), "1\n");
+    assert_eq!(text(&output.stderr), "Error: a{1..1000}{1..1000}: brace expansion produces more than 100000 words\n");
+
+    let (shell, _) = shell_with_programs();
+    assert_eq!(shell.eval("echo {1..100000000}"), 1);
+    assert_eq!(shell.eval("echo {a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}{a,b}"), 1);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use jomsole::Builtin::BuiltinRegistry;
+use jomsole::fake::FakePathResolver::FakePathResolver;
+use jomsole::jsh::JshChecker::check_script;
+use common::{run_jomsole, text, TempDirectory};
+
+fn check(source: &str) -> Vec<String> {
+    let mut path_resolver = FakePathResolver::new();
+    path_resolver.add_command("ls", "/bin/ls");
+    path_resolver.add_command("echo", "/bin/echo");
+    return check_script(source, &path_resolver, &BuiltinRegistry::with_defaults()).iter()
+        .map(|diagnostic| diagnostic.describe("c.jsh"))
+        .collect();
+}
+
+#[test]
+fn unterminated_quotes_are_reported_at_the_opening_quote() {
+    assert_eq!(check("echo hi\necho \"unterminated\nls\n"), vec![
+        "c.jsh:2:6: error: Unterminated quoted string".to_string()
+    ]);
+}
+
+#[test]
+fn time_options_are_not_commands() {
+    assert!(check("time -p ls\ntime ls -l\n").is_empty());
+    assert_eq!(check("time -p missing\n"), vec![
+        "c.jsh:1:9: warning: command not found: missing".to_string()
+    ]);
+}
+
+#[test]
+fn checking_carries_on_after_each_syntax_error() {
+    assert_eq!(check("(\n  echo a\n  echo )\n)\nmissing\n{\n  echo |\n  |\n}\necho ( x\nls\n"), vec![
+        "c.jsh:4:1: error: Syntax error: unexpected `)`".to_string(),
+        "c.jsh:5:1: warning: command not found: missing".to_string(),
+        "c.jsh:8:3: error: Syntax error: unexpected `|`".to_string(),
+        "c.jsh:9:1: error: Syntax error: unexpected `}`".to_string(),
+        "c.jsh:10:6: error: Syntax error: unexpected `(`".to_string()
+    ]);
+}
+
+#[test]
+fn fmt_and_check_may_follow_other_options() {
+    let directory = TempDirectory::new("check-options");
+    let script = directory.write("c.jsh", "echo   (\n");
+    let script = script.to_str().unwrap();
+
+    let output = run_jomsole(&["-x", "--check", script], "");
+    assert_eq!(text(&output.stdout), format!("{}:1:8: error: Syntax error: unexpected `(`\n", script));
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = run_jomsole(&["--norc", "-n", script], "");
+    assert_eq!(output.status.code(), Some(1));
+
+    directory.write("c.jsh", "echo   a\n");
+    let output = run_jomsole(&["--norc", "fmt", "--check", script], "");
+    assert!(text(&output.stdout).contains("+echo a\n"), "{}", text(&output.stdout));
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,156 @@
+//! Helpers shared by the integration tests.
+#![allow(dead_code)]
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::rc::Rc;
+use jomsole::CommandInterface::ScriptCommandInterface;
+use jomsole::PathResolver::PathResolver;
+use jomsole::Shell::Shell;
+use jomsole::ShellState::Variable;
+use jomsole::fake::FakePathResolver::FakePathResolver;
+use jomsole::fake::FakeProcessSpawner::FakeProcessSpawner;
+
+/// The programs [`fake_programs`] provides, each found at `/bin/<name>`.
+pub const PROGRAMS: [&str; 5] = ["cat", "echo", "true", "false", "bytes"];
+
+/// `cat` copies its input, `echo` prints its arguments, `true` and `false` exit with 0 and 1, and
+/// `bytes` prints a line that is not valid UTF-8.
+pub fn fake_programs() -> FakeProcessSpawner {
+    let mut process_spawner = FakeProcessSpawner::new();
+    process_spawner.add_program("/bin/cat", |_, input, output| {
+        write!(output, "{}", input).unwrap();
+        return 0;
+    });
+    process_spawner.add_program("/bin/echo", |arguments, _, output| {
+        writeln!(output, "{}", arguments.join(" ")).unwrap();
+        return 0;
+    });
+    process_spawner.add_program("/bin/true", |_, _, _| 0);
+    process_spawner.add_program("/bin/false", |_, _, _| 1);
+    process_spawner.add_program("/bin/bytes", |_, _, output| {
+        output.write_all(b"ok \xff\n").unwrap();
+        return 0;
+    });
+    return process_spawner;
+}
+
+pub fn fake_commands() -> FakePathResolver {
+    let mut path_resolver = FakePathResolver::new();
+    for name in PROGRAMS {
+        path_resolver.add_command(name, &format!("/bin/{}", name));
+    }
+    return path_resolver;
+}
+
+pub fn build_shell(path_resolver: Rc<dyn PathResolver>, process_spawner: Rc<FakeProcessSpawner>) -> Shell {
+    return Shell::builder()
+        .path_resolver(path_resolver)
+        .process_spawner(process_spawner)
+        .build()
+        .unwrap();
+}
+
+/// A shell running [`fake_programs`], with the spawner to read their output from.
+pub fn shell_with_programs() -> (Shell, Rc<FakeProcessSpawner>) {
+    let process_spawner = Rc::new(fake_programs());
+    return (build_shell(Rc::new(fake_commands()), process_spawner.clone()), process_spawner);
+}
+
+/// A shell running [`fake_programs`] that reads its commands from the script at `path`, the way
+/// `jomsole script.jsh` does.
+pub fn shell_for_script(path: &Path) -> (Shell, Rc<FakeProcessSpawner>) {
+    let process_spawner = Rc::new(fake_programs());
+    let shell = Shell::builder()
+        .path_resolver(Rc::new(fake_commands()))
+        .process_spawner(process_spawner.clone())
+        .command_interface(ScriptCommandInterface::new(BufReader::new(File::open(path).unwrap())))
+        .build()
+        .unwrap();
+    return (shell, process_spawner);
+}
+
+/// The `jomsole` binary with `arguments`, ready for [`run_with_input`].
+pub fn jomsole(arguments: &[&str]) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_Jomsole"));
+    command.args(arguments);
+    return command;
+}
+
+/// Runs the `jomsole` binary with `arguments`, feeding it `input` on stdin.
+pub fn run_jomsole(arguments: &[&str], input: &str) -> Output {
+    return run_with_input(jomsole(arguments), input);
+}
+
+pub fn run_with_input(mut command: Command, input: &str) -> Output {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    return child.wait_with_output().unwrap();
+}
+
+pub fn text(bytes: &[u8]) -> String {
+    return String::from_utf8_lossy(bytes).into_owned();
+}
+
+pub fn scalar(shell: &Shell, name: &str) -> Option<String> {
+    return match shell.get_variable(name) {
+        Some(Variable::Scalar(value)) => Some(value),
+        _ => None
+    };
+}
+
+pub fn array(shell: &Shell, name: &str) -> Option<Vec<String>> {
+    return match shell.get_variable(name) {
+        Some(Variable::Array(values)) => Some(values),
+        _ => None
+    };
+}
+
+pub fn strings(values: &[&str]) -> Vec<String> {
+    return values.iter().map(|value| value.to_string()).collect();
+}
+
+/// A directory under the system temp directory, removed again when dropped.
+pub struct TempDirectory {
+    path: PathBuf
+}
+
+impl TempDirectory {
+    pub fn new(name: &str) -> TempDirectory {
+        // Tests run in parallel, possibly with the same name.
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("jomsole-{}-{}-{}", name, std::process::id(), count));
+        fs::create_dir_all(&path).unwrap();
+        return TempDirectory {
+            path: path
+        };
+    }
+
+    pub fn path(&self) -> &Path {
+        return &self.path;
+    }
+
+    /// Writes `contents` to `name` inside the directory, creating parent directories as needed.
+    pub fn write(&self, name: &str, contents: &str) -> PathBuf {
+        let path = self.path.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, contents).unwrap();
+        return path;
+    }
+}
+
+impl Drop for TempDirectory {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
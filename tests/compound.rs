@@ -0,0 +1,50 @@
+mod common;
+
+use std::path::PathBuf;
+use common::{scalar, shell_for_script, shell_with_programs, TempDirectory};
+
+#[test]
+fn subshells_keep_their_state_to_themselves() {
+    let (shell, process_spawner) = shell_with_programs();
+    let directory = shell.state().borrow().current_directory().to_path_buf();
+
+    assert_eq!(shell.eval("(X=inner; cd /; echo $X)"), 0);
+    assert_eq!(process_spawner.take_output(), "inner\n");
+    assert_eq!(scalar(&shell, "X"), None);
+    assert_eq!(shell.state().borrow().current_directory(), directory);
+}
+
+#[test]
+fn groups_run_in_the_current_shell() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("{ X=outer; cd /; }"), 0);
+    assert_eq!(scalar(&shell, "X").as_deref(), Some("outer"));
+    assert_eq!(shell.state().borrow().current_directory(), PathBuf::from("/"));
+    assert_eq!(process_spawner.take_output(), "");
+}
+
+#[test]
+fn compound_commands_compose_with_pipes_redirections_and_lists() {
+    let (shell, process_spawner) = shell_with_programs();
+    let directory = TempDirectory::new("compound");
+    let file = directory.path().join("out");
+
+    assert_eq!(shell.eval(&format!("{{ echo one; echo two; }} > {}", file.display())), 0);
+    assert_eq!(std::fs::read_to_string(&file).unwrap(), "one\ntwo\n");
+    assert_eq!(shell.eval("(echo a; echo b) | cat"), 0);
+    assert_eq!(process_spawner.take_output(), "a\nb\n");
+    assert_eq!(shell.eval("(false) || { echo recovered; }"), 0);
+    assert_eq!(shell.eval("{ true; false; } && echo skipped"), 1);
+    assert_eq!(process_spawner.take_output(), "recovered\n");
+}
+
+#[test]
+fn scripts_can_open_a_group_on_a_line_of_its_own() {
+    let directory = TempDirectory::new("group-script");
+    let script = directory.write("group.jsh", "{\n    false\n    echo one\n} && echo two\n(\n    echo three\n) | cat\n");
+
+    let (shell, process_spawner) = shell_for_script(&script);
+    assert_eq!(shell.run(), 0);
+    assert_eq!(process_spawner.take_output(), "one\ntwo\nthree\n");
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+
+use common::{jomsole, run_with_input, text, TempDirectory};
+
+fn directory_tree() -> (TempDirectory, PathBuf) {
+    let directory = TempDirectory::new("directories");
+    directory.write("a/sub/.keep", "");
+    directory.write("b/.keep", "");
+    let root = std::fs::canonicalize(directory.path()).unwrap();
+    return (directory, root);
+}
+
+fn run_in(root: &Path, script: &str) -> (String, String) {
+    let mut command = jomsole(&["-c", script]);
+    command.current_dir(root);
+    let output = run_with_input(command, "");
+    return (text(&output.stdout), text(&output.stderr));
+}
+
+#[test]
+fn cd_dash_returns_to_the_previous_directory_and_prints_it() {
+    let (_directory, root) = directory_tree();
+
+    let (stdout, stderr) = run_in(&root, "cd a; cd ../b; cd -; pwd; cd -; echo $OLDPWD");
+    let (a, b) = (root.join("a"), root.join("b"));
+    assert_eq!(stdout, format!("{}\n{}\n{}\n{}\n", a.display(), a.display(), b.display(), a.display()));
+    assert_eq!(stderr, "");
+}
+
+#[test]
+fn cd_searches_cdpath_only_for_plain_relative_names() {
+    let (_directory, root) = directory_tree();
+
+    let script = format!("CDPATH={}; cd sub; pwd; cd {}; cd b; pwd; cd ./sub; echo $?", root.join("a").display(), root.display());
+    let (stdout, stderr) = run_in(&root, &script);
+    let (sub, b) = (root.join("a/sub"), root.join("b"));
+    assert_eq!(stdout, format!("{}\n{}\n{}\n1\n", sub.display(), sub.display(), b.display()));
+    assert_eq!(stderr, "cd: ./sub: No such file or directory (os error 2)\n");
+}
+
+#[test]
+fn pushd_and_popd_keep_a_stack_of_directories() {
+    let (_directory, root) = directory_tree();
+
+    let (stdout, stderr) = run_in(&root, "pushd a; pushd ../b; popd; pwd; popd; popd; echo $?; pushd; echo $?");
+    let (a, b) = (root.join("a"), root.join("b"));
+    assert_eq!(
+        stdout,
+        format!(
+            "{a} {root}\n{b} {a} {root}\n{a} {root}\n{a}\n{root}\n1\n1\n",
+            a = a.display(), b = b.display(), root = root.display()
+        )
+    );
+    assert_eq!(stderr, "popd: directory stack empty\npushd: no other directory\n");
+}
+
+#[test]
+fn pushd_rotates_the_stack_and_dirs_lists_it() {
+    let (_directory, root) = directory_tree();
+
+    let (stdout, _) = run_in(&root, "pushd a > /dev/null; pushd sub > /dev/null; pushd +1 > /dev/null; pwd; dirs -v");
+    let (a, sub) = (root.join("a"), root.join("a/sub"));
+    assert_eq!(
+        stdout,
+        format!(
+            "{a}\n 0  {a}\n 1  {root}\n 2  {sub}\n",
+            a = a.display(), sub = sub.display(), root = root.display()
+        )
+    );
+}
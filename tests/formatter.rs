@@ -0,0 +1,46 @@
+mod common;
+
+use jomsole::jsh::JshFormatter::format_script;
+use common::{shell_for_script, TempDirectory};
+
+fn assert_stable(source: &str) {
+    let formatted = format_script(source).unwrap();
+    assert_eq!(formatted, source);
+    assert_eq!(format_script(&formatted).unwrap(), formatted);
+}
+
+#[test]
+fn quoted_reserved_words_stay_quoted_as_command_names() {
+    assert_stable("\"time\" ls\n");
+    assert_stable("time \"time\" ls\n");
+}
+
+#[test]
+fn quoted_reserved_words_are_unquoted_as_arguments() {
+    assert_eq!(format_script("echo \"time\"\n").unwrap(), "echo time\n");
+    assert_stable("time ls -l\n");
+}
+
+#[test]
+fn several_here_documents_on_one_line_keep_their_bodies() {
+    assert_stable("cat <<A; cat <<'B'\na $HOME\nA\nb\n\tB\nB\n");
+    assert_eq!(format_script("cat <<-A\n\tx\n\tA\n").unwrap(), "cat <<-A\n\tx\nA\n");
+}
+
+// Runs `source` as a script file and returns its status and output.
+fn run_script(directory: &TempDirectory, name: &str, source: &str) -> (i32, String) {
+    let (shell, process_spawner) = shell_for_script(&directory.write(name, source));
+    return (shell.run(), process_spawner.take_output());
+}
+
+#[test]
+fn formatted_scripts_run_like_the_original() {
+    let source = "{ echo one\n  false\n  echo two; } && echo three\n( echo a;echo b )|cat\n{ true; } || echo never\n";
+    let formatted = format_script(source).unwrap();
+    assert_ne!(formatted, source);
+
+    let directory = TempDirectory::new("formatted");
+    let original = run_script(&directory, "original.jsh", source);
+    assert_eq!(original, (0, "one\ntwo\nthree\na\nb\n".to_string()));
+    assert_eq!(run_script(&directory, "formatted.jsh", &formatted), original);
+}
@@ -0,0 +1,63 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+use common::{run_jomsole, text, TempDirectory};
+
+fn program(directory: &TempDirectory, name: &str, message: &str) -> PathBuf {
+    let path = directory.write(name, &format!("#!/bin/sh\necho {}\n", message));
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    return path;
+}
+
+#[test]
+fn commands_are_remembered_with_their_hits_until_path_changes() {
+    let directory = TempDirectory::new("hash-path");
+    let first = program(&directory, "first/tool", "first");
+    let second = program(&directory, "second/tool", "second");
+    let script = format!(
+        "PATH={}; hash; tool; tool; hash; hash -t tool; PATH={}; hash; tool; hash",
+        directory.path().join("first").display(),
+        directory.path().join("second").display()
+    );
+
+    let output = run_jomsole(&["-c", &script], "");
+    assert_eq!(
+        text(&output.stdout),
+        format!(
+            "first\nfirst\nhits\tcommand\n   2\t{}\n{}\nsecond\nhits\tcommand\n   1\t{}\n",
+            first.display(), first.display(), second.display()
+        )
+    );
+    assert_eq!(text(&output.stderr), "hash: hash table empty\nhash: hash table empty\n");
+}
+
+#[test]
+fn hash_adds_and_forgets_entries() {
+    let directory = TempDirectory::new("hash-entries");
+    let first = program(&directory, "first/tool", "first");
+    let second = program(&directory, "second/tool", "second");
+    let script = format!(
+        "PATH={}; hash tool; hash -l; hash -p {} tool; tool; hash -d tool; tool; hash -r; hash",
+        directory.path().join("first").display(),
+        second.display()
+    );
+
+    let output = run_jomsole(&["-c", &script], "");
+    assert_eq!(text(&output.stdout), format!("hash -p {} tool\nsecond\nfirst\n", first.display()));
+    assert_eq!(text(&output.stderr), "hash: hash table empty\n");
+}
+
+#[test]
+fn hash_reports_unknown_commands_and_options() {
+    let output = run_jomsole(&["-c", "hash missing-command; echo $?; hash -t missing-command; echo $?; hash -x; echo $?"], "");
+    assert_eq!(text(&output.stdout), "1\n1\n2\n");
+    assert_eq!(
+        text(&output.stderr),
+        "hash: missing-command: not found\nhash: missing-command: not found\nhash: -x: invalid option\n"
+    );
+}
@@ -0,0 +1,33 @@
+mod common;
+
+use common::{run_jomsole, text};
+
+#[test]
+fn command_strings_run_once_and_exit_with_their_status() {
+    let output = run_jomsole(&["-c", "echo one | cat; false"], "");
+    assert_eq!(text(&output.stdout), "one\n");
+    assert_eq!(text(&output.stderr), "");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn command_strings_take_the_script_name_and_arguments_after_them() {
+    let output = run_jomsole(&["-c", "echo $0 $1 $#", "name", "a"], "");
+    assert_eq!(text(&output.stdout), "name a 1\n");
+    assert!(output.status.success());
+}
+
+#[test]
+fn command_option_needs_a_command_string() {
+    let output = run_jomsole(&["-c"], "");
+    assert_eq!(text(&output.stderr), "-c: option requires an argument\n");
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn piped_input_runs_without_prompts_or_banners() {
+    let output = run_jomsole(&[], "echo one\necho two | cat\nfalse\n");
+    assert_eq!(text(&output.stdout), "one\ntwo\n");
+    assert_eq!(text(&output.stderr), "");
+    assert_eq!(output.status.code(), Some(1));
+}
@@ -0,0 +1,216 @@
+use std::ffi::{OsStr, OsString};
+use std::path::Path;
+use jomsole::PathResolver::PathResolver;
+use jomsole::SearchPathResolver::{split_search_path, SearchPathResolver};
+use jomsole::fake::FakeFileSystem::FakeFileSystem;
+use jomsole::nt::WindowsPathResolver;
+#[cfg(unix)]
+use jomsole::unix::UnixPathResolver;
+
+const WINDOWS_PATH: &str = "C:\\Windows\\System32;C:\\Tools;;C:\\Python";
+const WINDOWS_PATHEXT: &str = ".COM;.EXE;.BAT;.CMD";
+
+fn windows_resolver(files: &[&str]) -> SearchPathResolver {
+    let mut file_system = FakeFileSystem::new('\\');
+    for file in files {
+        file_system.add_file(file);
+    }
+    return WindowsPathResolver::with_probe(
+        OsStr::new(WINDOWS_PATH),
+        OsStr::new(WINDOWS_PATHEXT),
+        Box::new(file_system)
+    );
+}
+
+#[cfg(unix)]
+fn unix_resolver(files: &[&str]) -> SearchPathResolver {
+    let mut file_system = FakeFileSystem::new('/');
+    for file in files {
+        file_system.add_file(file);
+    }
+    return UnixPathResolver::with_probe(OsStr::new("/usr/local/bin:/usr/bin:/bin"), Box::new(file_system));
+}
+
+fn resolve(resolver: &SearchPathResolver, command_name: &str) -> Option<String> {
+    return resolver.resolve_command_global(command_name).ok()
+        .map(|path| path.to_string_lossy().to_string());
+}
+
+#[test]
+fn earlier_directories_win() {
+    let resolver = windows_resolver(&["C:\\Tools\\python.exe", "C:\\Python\\python.exe"]);
+    assert_eq!(resolve(&resolver, "python").as_deref(), Some("C:\\Tools\\python.exe"));
+}
+
+#[test]
+fn directory_order_beats_extension_order() {
+    let resolver = windows_resolver(&["C:\\Tools\\build.exe", "C:\\Python\\build.com"]);
+    assert_eq!(resolve(&resolver, "build").as_deref(), Some("C:\\Tools\\build.exe"));
+}
+
+#[test]
+fn extensions_are_tried_in_pathext_order() {
+    let resolver = windows_resolver(&["C:\\Tools\\build.cmd", "C:\\Tools\\build.com", "C:\\Tools\\build.exe"]);
+    assert_eq!(resolve(&resolver, "build").as_deref(), Some("C:\\Tools\\build.com"));
+}
+
+#[test]
+fn bare_name_is_tried_after_extensions() {
+    let resolver = windows_resolver(&["C:\\Tools\\make", "C:\\Tools\\make.bat"]);
+    assert_eq!(resolve(&resolver, "make").as_deref(), Some("C:\\Tools\\make.bat"));
+
+    let resolver = windows_resolver(&["C:\\Tools\\make"]);
+    assert_eq!(resolve(&resolver, "make").as_deref(), Some("C:\\Tools\\make"));
+}
+
+#[test]
+fn explicit_extension_is_tried_as_is_first() {
+    let resolver = windows_resolver(&["C:\\Tools\\setup.exe", "C:\\Tools\\setup.exe.com"]);
+    assert_eq!(resolve(&resolver, "setup.exe").as_deref(), Some("C:\\Tools\\setup.exe"));
+    assert_eq!(resolve(&resolver, "SETUP.EXE").as_deref(), Some("C:\\Tools\\setup.exe"));
+}
+
+#[test]
+fn windows_names_are_case_insensitive() {
+    let resolver = windows_resolver(&["C:\\WINDOWS\\SYSTEM32\\CMD.EXE"]);
+    assert_eq!(resolve(&resolver, "cmd").as_deref(), Some("C:\\WINDOWS\\SYSTEM32\\CMD.EXE"));
+}
+
+#[test]
+#[cfg(unix)]
+fn unix_names_are_case_sensitive_and_exact() {
+    let resolver = unix_resolver(&["/usr/bin/Make", "/bin/ls.exe", "/bin/ls"]);
+    assert_eq!(resolve(&resolver, "make"), None);
+    assert_eq!(resolve(&resolver, "Make").as_deref(), Some("/usr/bin/Make"));
+    assert_eq!(resolve(&resolver, "ls").as_deref(), Some("/bin/ls"));
+}
+
+#[test]
+fn missing_commands_are_errors() {
+    let resolver = windows_resolver(&["C:\\Tools\\python.exe"]);
+    assert!(resolver.resolve_command_global("perl").is_err());
+    assert!(resolver.resolve_command_all("perl").is_empty());
+}
+
+#[test]
+fn all_matches_are_listed_in_search_order_with_their_extension() {
+    let resolver = windows_resolver(&[
+        "C:\\Python\\python.exe",
+        "C:\\Tools\\python.bat",
+        "C:\\Tools\\python.com"
+    ]);
+    let matches: Vec<(String, String)> = resolver.resolve_command_all("python").into_iter()
+        .map(|command_match| (
+            command_match.path.to_string_lossy().to_string(),
+            command_match.extension.to_string_lossy().to_string()
+        ))
+        .collect();
+
+    assert_eq!(matches, vec![
+        ("C:\\Tools\\python.com".to_string(), ".COM".to_string()),
+        ("C:\\Tools\\python.bat".to_string(), ".BAT".to_string()),
+        ("C:\\Python\\python.exe".to_string(), ".EXE".to_string())
+    ]);
+}
+
+#[test]
+fn directories_listed_twice_are_reported_once() {
+    let resolver = windows_resolver(&["C:\\Tools\\python.exe"]);
+    resolver.set_search_path(OsStr::new("C:\\Tools;C:\\Python;C:\\Tools"));
+    assert_eq!(resolver.resolve_command_all("python").len(), 1);
+}
+
+#[test]
+#[cfg(unix)]
+fn symbolic_links_are_run_by_their_own_path() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+    use jomsole::SearchPathResolver::SystemFileSystem;
+
+    let directory = std::env::temp_dir().join(format!("jomsole-symlink-{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let target = directory.join("busybox");
+    std::fs::write(&target, "").unwrap();
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o755)).unwrap();
+    let _ = std::fs::remove_file(directory.join("ls"));
+    symlink(&target, directory.join("ls")).unwrap();
+
+    let resolver = UnixPathResolver::with_probe(directory.as_os_str(), Box::new(SystemFileSystem::new()));
+    let path = resolver.resolve_command_global("ls");
+    std::fs::remove_dir_all(&directory).unwrap();
+    assert_eq!(path.unwrap(), directory.join("ls").into_os_string());
+}
+
+#[test]
+fn search_path_can_change() {
+    let resolver = windows_resolver(&["C:\\Tools\\python.exe", "C:\\Python\\python.exe"]);
+    resolver.set_search_path(OsStr::new("C:\\Python;C:\\Tools"));
+    assert_eq!(resolve(&resolver, "python").as_deref(), Some("C:\\Python\\python.exe"));
+}
+
+#[test]
+fn search_path_drops_empty_entries() {
+    assert_eq!(split_search_path(OsStr::new(WINDOWS_PATH), ';'), vec![
+        OsString::from("C:\\Windows\\System32"),
+        OsString::from("C:\\Tools"),
+        OsString::from("C:\\Python")
+    ]);
+    assert_eq!(split_search_path(OsStr::new(":/bin::/usr/bin:"), ':'), vec![
+        OsString::from("/bin"),
+        OsString::from("/usr/bin")
+    ]);
+    assert!(split_search_path(OsStr::new(""), ';').is_empty());
+}
+
+#[test]
+#[cfg(unix)]
+fn non_executable_files_are_skipped_on_the_search_path() {
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_non_executable_file("/usr/bin/tool");
+    file_system.add_file("/bin/tool");
+    let resolver = UnixPathResolver::with_probe(OsStr::new("/usr/bin:/bin"), Box::new(file_system));
+
+    assert_eq!(resolve(&resolver, "tool").as_deref(), Some("/bin/tool"));
+    assert_eq!(resolver.resolve_command_all("tool").len(), 1);
+}
+
+#[test]
+#[cfg(unix)]
+fn direct_paths_resolve_relative_to_the_current_directory() {
+    let resolver = unix_resolver(&["/work/tools/build", "/usr/bin/env", "/work/my script"]);
+    let local = |given_path: &str| resolver.resolve_command_local(Path::new("/work"), given_path).ok()
+        .map(|path| path.to_string_lossy().to_string());
+
+    assert_eq!(local("tools/build").as_deref(), Some("/work/tools/build"));
+    assert_eq!(local("/usr/bin/env").as_deref(), Some("/usr/bin/env"));
+    assert_eq!(local("my script").as_deref(), Some("/work/my script"));
+    assert_eq!(local("build"), None);
+    assert_eq!(local("./tools/../tools/./build").as_deref(), Some("/work/tools/build"));
+    assert_eq!(local("../work/tools/build").as_deref(), Some("/work/tools/build"));
+    assert_eq!(local("../../../usr/bin/env").as_deref(), Some("/usr/bin/env"));
+}
+
+#[test]
+#[cfg(unix)]
+fn direct_paths_must_be_executable() {
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_non_executable_file("/work/notes.txt");
+    let resolver = UnixPathResolver::with_probe(OsStr::new("/bin"), Box::new(file_system));
+
+    let error = resolver.resolve_command_local(Path::new("/work"), "notes.txt").unwrap_err();
+    assert_eq!(error.reason(), "notes.txt: permission denied");
+    assert_eq!(error.exit_code(), 126);
+    let error = resolver.resolve_command_local(Path::new("/work"), "missing").unwrap_err();
+    assert_eq!(error.reason(), "missing: no such file");
+    assert_eq!(error.exit_code(), 127);
+}
+
+#[test]
+#[cfg(unix)]
+fn windows_direct_paths_try_extensions() {
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_file("/work/build.exe");
+    let resolver = WindowsPathResolver::with_probe(OsStr::new(""), OsStr::new(WINDOWS_PATHEXT), Box::new(file_system));
+
+    let path = resolver.resolve_command_local(Path::new("/work"), "build").unwrap();
+    assert_eq!(path, OsString::from("/work/build.exe"));
+}
@@ -0,0 +1,62 @@
+#![cfg(unix)]
+
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+
+use common::{jomsole, run_jomsole, run_with_input, text, TempDirectory};
+
+#[test]
+fn umask_prints_and_sets_the_mask_in_octal_and_symbolic_form() {
+    let output = run_jomsole(&["-c", "umask 027; umask; umask -S; umask u=rwx,g=rx,o=; umask; umask g-w,o-rwx; umask"], "");
+    assert_eq!(text(&output.stdout), "0027\nu=rwx,g=rx,o=\n0027\n0027\n");
+    assert_eq!(text(&output.stderr), "");
+}
+
+#[test]
+fn umask_applies_to_children_and_redirections_but_not_past_a_subshell() {
+    let directory = TempDirectory::new("umask");
+    let mut command = jomsole(&["-c", "umask 022; (umask 077); umask; umask 077; sh -c umask; echo secret > secret"]);
+    command.current_dir(directory.path());
+    let output = run_with_input(command, "");
+    assert_eq!(text(&output.stdout), "0022\n0077\n");
+
+    let mode = std::fs::metadata(directory.path().join("secret")).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+}
+
+#[test]
+fn umask_rejects_invalid_modes() {
+    let output = run_jomsole(&["-c", "umask 999; echo $?"], "");
+    assert_eq!(text(&output.stdout), "1\n");
+    assert_eq!(text(&output.stderr), "umask: 999: invalid mode\n");
+}
+
+#[test]
+fn ulimit_sets_limits_for_children_but_not_past_a_subshell() {
+    let output = run_jomsole(&["-c", "ulimit -S -n 64; ulimit -n; sh -c \"ulimit -n\"; (ulimit -S -n 32); ulimit -n"], "");
+    assert_eq!(text(&output.stdout), "64\n64\n64\n");
+    assert_eq!(text(&output.stderr), "");
+}
+
+#[test]
+fn ulimit_reports_bad_options_and_values() {
+    let output = run_jomsole(&["-c", "ulimit -x; echo $?; ulimit -S -n bad; echo $?"], "");
+    assert_eq!(text(&output.stdout), "2\n1\n");
+    assert_eq!(text(&output.stderr), "ulimit: -x: invalid option\nulimit: bad: invalid number\n");
+}
+
+#[test]
+fn nice_runs_commands_with_an_adjusted_niceness() {
+    let output = run_jomsole(&["-c", "nice; nice -n 5 nice; nice -n 2 sh -c nice; nice"], "");
+    let values: Vec<i32> = text(&output.stdout).lines().map(|line| line.parse().unwrap()).collect();
+    let base = values[0];
+    assert_eq!(values, vec![base, (base + 5).min(19), (base + 2).min(19), base]);
+}
+
+#[test]
+fn nice_reports_invalid_adjustments() {
+    let output = run_jomsole(&["-c", "nice -n x true"], "");
+    assert_eq!(text(&output.stderr), "Error: nice: x: invalid adjustment\n");
+    assert!(!output.status.success());
+}
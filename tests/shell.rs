@@ -0,0 +1,138 @@
+mod common;
+
+use std::env;
+use std::path::PathBuf;
+use std::rc::Rc;
+use jomsole::ShellState::Variable;
+use common::{array, build_shell, fake_programs, scalar, shell_with_programs, strings, TempDirectory};
+
+#[test]
+fn pipelines_exit_with_the_last_stage() {
+    let (shell, _) = shell_with_programs();
+
+    assert_eq!(shell.eval("false | true"), 0);
+    assert_eq!(array(&shell, "PIPESTATUS"), Some(strings(&["1", "0"])));
+    assert_eq!(shell.eval("true | false"), 1);
+    assert_eq!(array(&shell, "PIPESTATUS"), Some(strings(&["0", "1"])));
+}
+
+#[test]
+fn pipefail_reports_the_last_failing_stage() {
+    let (shell, _) = shell_with_programs();
+
+    assert_eq!(shell.eval("set -o pipefail\nfalse | true"), 1);
+    assert_eq!(array(&shell, "PIPESTATUS"), Some(strings(&["1", "0"])));
+    assert_eq!(shell.eval("true | true"), 0);
+}
+
+#[test]
+fn missing_commands_exit_with_127_and_the_pipeline_carries_on() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("nosuch"), 127);
+    assert_eq!(shell.eval("echo $?"), 0);
+    assert_eq!(process_spawner.take_output(), "127\n");
+
+    assert_eq!(shell.eval("nosuch | cat"), 0);
+    assert_eq!(array(&shell, "PIPESTATUS"), Some(strings(&["127", "0"])));
+}
+
+#[test]
+fn redirections_write_and_read_files() {
+    let (shell, process_spawner) = shell_with_programs();
+    let directory = TempDirectory::new("redirect");
+    let file = directory.path().join("out");
+
+    assert_eq!(shell.eval(&format!("echo one > {0}\necho two >> {0}\ncat < {0}", file.display())), 0);
+    assert_eq!(process_spawner.take_output(), "one\ntwo\n");
+}
+
+#[test]
+fn invalid_utf8_output_is_replaced() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("bytes | cat"), 0);
+    assert_eq!(process_spawner.take_output(), "ok \u{fffd}\n");
+}
+
+#[test]
+fn unset_variables_expand_from_the_environment() {
+    let (shell, _) = shell_with_programs();
+
+    assert_eq!(shell.eval("PATH=/opt/bin:$PATH"), 0);
+    let expected = format!("/opt/bin:{}", env::var("PATH").unwrap_or_default());
+    assert_eq!(scalar(&shell, "PATH"), Some(expected));
+}
+
+#[cfg(unix)]
+#[test]
+fn prepending_to_path_keeps_the_existing_directories() {
+    use std::ffi::OsStr;
+    use jomsole::fake::FakeFileSystem::FakeFileSystem;
+    use jomsole::unix::UnixPathResolver;
+
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_file("/usr/bin/tool");
+    file_system.add_file("/opt/bin/other");
+    let path_resolver = UnixPathResolver::with_probe(OsStr::new("/usr/bin"), Box::new(file_system));
+
+    let mut process_spawner = fake_programs();
+    process_spawner.add_program("/usr/bin/tool", |_, _, _| 0);
+    process_spawner.add_program("/opt/bin/other", |_, _, _| 0);
+
+    let shell = build_shell(Rc::new(path_resolver), Rc::new(process_spawner));
+    shell.set_variable("PATH", Variable::Scalar("/usr/bin".to_string()));
+
+    assert_eq!(shell.eval("PATH=/opt/bin:$PATH\ntool\nother"), 0);
+    assert_eq!(scalar(&shell, "PATH").as_deref(), Some("/opt/bin:/usr/bin"));
+}
+
+#[cfg(unix)]
+#[test]
+fn type_lists_each_file_once() {
+    use std::ffi::OsStr;
+    use jomsole::fake::FakeFileSystem::FakeFileSystem;
+    use jomsole::unix::UnixPathResolver;
+
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_file("/usr/bin/tool");
+    file_system.add_file("/bin/tool");
+    file_system.add_file("/bin/cat");
+    let path_resolver = UnixPathResolver::with_probe(OsStr::new("/usr/bin:/bin:/usr/bin"), Box::new(file_system));
+
+    let process_spawner = Rc::new(fake_programs());
+    let shell = build_shell(Rc::new(path_resolver), process_spawner.clone());
+
+    assert_eq!(shell.eval("type -a tool | cat"), 0);
+    assert_eq!(process_spawner.take_output(), "tool is /usr/bin/tool\ntool is /bin/tool\n");
+}
+
+#[test]
+fn here_document_terminators_are_indented_only_with_strip_tabs() {
+    let (shell, process_spawner) = shell_with_programs();
+
+    assert_eq!(shell.eval("cat <<A; cat <<-B\n\tA\nA\n\tb\n\tB\n"), 0);
+    assert_eq!(process_spawner.take_output(), "\tA\nb\n");
+}
+
+#[cfg(unix)]
+#[test]
+fn direct_paths_that_cannot_run_exit_with_126() {
+    use std::ffi::OsStr;
+    use jomsole::fake::FakeFileSystem::FakeFileSystem;
+    use jomsole::unix::UnixPathResolver;
+
+    let mut file_system = FakeFileSystem::new('/');
+    file_system.add_file("/work/tools/build");
+    file_system.add_non_executable_file("/work/notes.txt");
+    let path_resolver = UnixPathResolver::with_probe(OsStr::new("/bin"), Box::new(file_system));
+
+    let mut process_spawner = fake_programs();
+    process_spawner.add_program("/work/tools/build", |_, _, _| 3);
+    let shell = build_shell(Rc::new(path_resolver), Rc::new(process_spawner));
+    shell.state().borrow_mut().set_current_directory(PathBuf::from("/work"));
+
+    assert_eq!(shell.eval("./notes.txt"), 126);
+    assert_eq!(shell.eval("./missing"), 127);
+    assert_eq!(shell.eval("tools/../tools/./build"), 3);
+}
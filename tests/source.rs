@@ -0,0 +1,57 @@
+mod common;
+
+use common::{jomsole, run_jomsole, run_with_input, text, TempDirectory};
+
+#[test]
+fn failing_commands_in_sourced_files_are_reported_with_file_and_line() {
+    let directory = TempDirectory::new("source-errors");
+    let library = directory.write("lib.jsh", "X=1\nmissing-command arg | cat\n");
+
+    let output = run_jomsole(&[], &format!("source {}\n", library.display()));
+    let library = std::fs::canonicalize(&library).unwrap();
+    assert_eq!(text(&output.stderr), format!("{}:2: missing-command: command not found\n", library.display()));
+}
+
+#[test]
+fn include_runs_each_library_once_from_the_including_directory() {
+    let directory = TempDirectory::new("include");
+    directory.write("lib/a.jsh", "include b.jsh\nORDER=${ORDER}a\n");
+    directory.write("lib/b.jsh", "include a.jsh\nORDER=${ORDER}b\n");
+    let script = directory.write("main.jsh", "include lib/a.jsh\ninclude lib/b.jsh\ninclude lib/a.jsh\necho $ORDER\n");
+
+    let output = run_jomsole(&[script.to_str().unwrap()], "");
+    assert_eq!(text(&output.stdout), "ba\n");
+    assert_eq!(text(&output.stderr), "");
+    assert!(output.status.success());
+}
+
+#[test]
+fn source_arguments_replace_the_positional_parameters_only_while_the_file_runs() {
+    let directory = TempDirectory::new("source-arguments");
+    directory.write("args.jsh", "echo in $# $1\n");
+    let script = directory.write("main.jsh", "source args.jsh a b\necho out $1 $#\nsource args.jsh\n");
+
+    let mut command = jomsole(&[script.to_str().unwrap(), "outer"]);
+    command.current_dir(directory.path());
+    let output = run_with_input(command, "");
+    assert_eq!(text(&output.stdout), "in 2 a\nout outer 1\nin 1 outer\n");
+    assert_eq!(text(&output.stderr), "");
+}
+
+#[test]
+fn sourcing_a_file_that_is_still_running_reports_the_cycle() {
+    let directory = TempDirectory::new("source-cycle");
+    let first = directory.write("first.jsh", "source second.jsh\n");
+    let second = directory.write("second.jsh", "source first.jsh\n");
+
+    let mut command = jomsole(&["-c", "source first.jsh; echo $?"]);
+    command.current_dir(directory.path());
+    let output = run_with_input(command, "");
+    let first = std::fs::canonicalize(&first).unwrap();
+    let second = std::fs::canonicalize(&second).unwrap();
+    assert_eq!(text(&output.stdout), "1\n");
+    assert_eq!(
+        text(&output.stderr),
+        format!("source: recursive inclusion: {} -> {} -> {}\n", first.display(), second.display(), first.display())
+    );
+}
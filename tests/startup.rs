@@ -0,0 +1,74 @@
+mod common;
+
+use std::path::PathBuf;
+use jomsole::ShellState::Variable;
+use jomsole::StartupFiles::{RcFile, StartupFiles};
+use common::{jomsole, run_with_input, scalar, shell_with_programs, text, TempDirectory};
+
+// A home directory whose profile and rc files each append their name to `$ORDER`.
+fn home_directory() -> TempDirectory {
+    let home = TempDirectory::new("home");
+    home.write("config/jomsole/profile", "ORDER=${ORDER}profile,\n");
+    home.write("config/jomsole/jomsolerc", "ORDER=${ORDER}rc,\n");
+    home.write("custom.jsh", "ORDER=${ORDER}custom,\n");
+    return home;
+}
+
+// Runs the startup files of `startup_files` with `home` as the home directory and returns `$ORDER`.
+fn run_startup_files(home: &TempDirectory, startup_files: StartupFiles) -> Option<String> {
+    let (shell, _) = shell_with_programs();
+    shell.set_variable("HOME", Variable::Scalar(home.path().display().to_string()));
+    shell.set_variable("XDG_CONFIG_HOME", Variable::Scalar(home.path().join("config").display().to_string()));
+    shell.set_variable("ORDER", Variable::Scalar(String::new()));
+    shell.state().borrow_mut().set_current_directory(home.path().to_path_buf());
+    shell.run_startup_files(&startup_files);
+    return scalar(&shell, "ORDER");
+}
+
+#[test]
+fn login_shells_run_the_profile_before_the_rc_file() {
+    let home = home_directory();
+
+    assert_eq!(run_startup_files(&home, StartupFiles::new(true, true)).as_deref(), Some("profile,rc,"));
+    assert_eq!(run_startup_files(&home, StartupFiles::new(true, false)).as_deref(), Some("profile,"));
+    assert_eq!(run_startup_files(&home, StartupFiles::new(false, true)).as_deref(), Some("rc,"));
+    assert_eq!(run_startup_files(&home, StartupFiles::new(false, false)).as_deref(), Some(""));
+}
+
+#[test]
+fn norc_and_rcfile_replace_the_default_rc_files() {
+    let home = home_directory();
+
+    let norc = StartupFiles::new(true, true).rc_file(RcFile::Disabled);
+    assert_eq!(run_startup_files(&home, norc).as_deref(), Some("profile,"));
+    let custom = StartupFiles::new(false, true).rc_file(RcFile::Custom(PathBuf::from("custom.jsh")));
+    assert_eq!(run_startup_files(&home, custom).as_deref(), Some("custom,"));
+}
+
+#[test]
+fn dotfiles_in_the_home_directory_are_the_fallback() {
+    let home = TempDirectory::new("dotfiles");
+    home.write(".jomsole_profile", "ORDER=${ORDER}profile,\n");
+    home.write(".jomsolerc", "ORDER=${ORDER}rc,\n");
+
+    assert_eq!(run_startup_files(&home, StartupFiles::new(true, true)).as_deref(), Some("profile,rc,"));
+}
+
+#[test]
+fn syntax_errors_only_skip_the_command_they_are_in() {
+    let home = TempDirectory::new("rc-errors");
+    home.write("config/jomsole/jomsolerc", "ORDER=${ORDER}one,\necho )\nORDER=${ORDER}two,\n{\n    ORDER=${ORDER}three,\n}\n");
+
+    assert_eq!(run_startup_files(&home, StartupFiles::new(false, true)).as_deref(), Some("one,two,three,"));
+}
+
+#[test]
+fn login_flag_runs_the_profile() {
+    let home = home_directory();
+    let mut command = jomsole(&["--login", "-c", "echo $ORDER"]);
+    command.env("HOME", home.path()).env("XDG_CONFIG_HOME", home.path().join("config")).env_remove("ORDER");
+
+    let output = run_with_input(command, "");
+    assert!(text(&output.stdout).ends_with("profile,\n"));
+    assert_eq!(text(&output.stderr), "");
+}
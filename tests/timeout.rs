@@ -0,0 +1,30 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{run_jomsole, text};
+
+#[test]
+fn commands_that_outlive_the_duration_exit_with_124() {
+    let started = Instant::now();
+    let output = run_jomsole(&["-c", "timeout 0.2 sleep 5; echo $?; timeout -s KILL 0.2 sleep 5; echo $?"], "");
+    assert_eq!(text(&output.stdout), "124\n124\n");
+    assert!(started.elapsed() < Duration::from_secs(4));
+}
+
+#[test]
+fn commands_that_finish_in_time_keep_their_status_and_output() {
+    let output = run_jomsole(&["-c", "timeout 5 echo hi | cat; timeout 5 false; echo $?; timeout 5s true; echo $?"], "");
+    assert_eq!(text(&output.stdout), "hi\n1\n0\n");
+    assert_eq!(text(&output.stderr), "");
+}
+
+#[test]
+fn bad_usage_exits_with_125_without_running_the_command() {
+    let output = run_jomsole(&["-c", "timeout bad echo no; echo $?; timeout -x 1 echo no; echo $?; timeout 1; echo $?"], "");
+    assert_eq!(text(&output.stdout), "125\n125\n125\n");
+    assert_eq!(
+        text(&output.stderr),
+        "timeout: invalid duration `bad`\ntimeout: unknown option -x\ntimeout: missing command\n"
+    );
+}